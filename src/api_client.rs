@@ -1,68 +1,274 @@
 use crate::{
+    connection_state::{ConnectionState, SharedState},
     errors::{DeribitError, Result},
-    models::{JSONRPCRequest, JSONRPCResponse, Request},
-    WSStream,
+    models::{
+        jsonrpc::ErrorDetail, subscription::TickerData, AssetKind, BuyRequest, Channel,
+        ClosePositionRequest, ClosePositionResponse, ClosePositionType, ComboLeg, Currency, Either,
+        GetBookSummaryByCurrencyRequest, GetBookSummaryByCurrencyResponse, GetComboDetailsRequest,
+        GetInstrumentsRequest, GetMarginsRequest, GetOrderBookRequest, GetOrderBookResponse,
+        GetPositionsRequest, InstrumentName, Interval, JSONRPCRequest, JSONRPCResponse,
+        LogoutRequest, PrivateSubscribeRequest, PublicSubscribeRequest, PublicTrade, Request,
+        SellRequest, SubscriptionData, SubscriptionMessage, SubscriptionParams,
+    },
+    rate_limit::RateLimiter,
+    DeribitSubscriptionClient, UserStream,
 };
-use anyhow::Error;
-use fehler::throws;
+use chrono::{DateTime, Utc};
+use fehler::{throw, throws};
 use futures::{
     channel::{mpsc, oneshot},
-    stream::SplitSink,
+    future::{ready, BoxFuture},
+    lock::Mutex as AsyncMutex,
+    stream::iter,
     task::{Context, Poll},
-    Future, SinkExt,
+    Future, FutureExt, SinkExt, Stream, StreamExt,
 };
 use log::{error, trace};
 use pin_project::pin_project;
-use serde::{de::DeserializeOwned, Serialize};
-use serde_json::{from_str, to_string};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{from_str, to_string, Value};
 use std::{
-    convert::Into, marker::PhantomData, pin::Pin, result::Result as StdResult, time::Duration,
+    collections::BTreeMap,
+    convert::Into,
+    marker::PhantomData,
+    pin::Pin,
+    result::Result as StdResult,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tokio::time::{error::Elapsed, timeout, Timeout};
+use tokio::time::{sleep, timeout};
 use tungstenite::Message;
 
+/// Where the servo hands the raw response of a call, or why it will never come.
+pub(crate) type Waiter = oneshot::Sender<Result<String>>;
+
+/// Requests to the servo that are not JSON-RPC calls.
+pub(crate) enum Control {
+    /// Sends a ping frame, resolving with the round trip time once its pong comes back
+    Ping(oneshot::Sender<Duration>),
+    /// Resolves with the number of calls waiting for their response
+    PendingCalls(oneshot::Sender<usize>),
+    /// Resolves once the in flight calls are answered, or the deadline passed, and the
+    /// connection is closed
+    Close(Duration, oneshot::Sender<()>),
+}
+
 pub struct DeribitAPIClient {
-    wstx: SplitSink<WSStream, Message>,
-    waiter_tx: mpsc::Sender<(i64, oneshot::Sender<String>)>,
+    wstx: mpsc::Sender<Message>,
+    waiter_tx: mpsc::Sender<(i64, Waiter)>,
+    control_tx: mpsc::Sender<Control>,
     timeout: Duration,
-    id: i64,
+    closed: bool,
+    // Shared with the calls retrying on their own
+    id: Arc<AtomicI64>,
+    default_currency: Option<Currency>,
+    default_instrument: Option<String>,
+    // Kept up to date by the servo, which sees the auth responses
+    pub(crate) auth_expiry: Arc<Mutex<Option<DateTime<Utc>>>>,
+    // Shared with the calls retrying on their own, whose re-sends draw from the same budget
+    rate_limiter: Option<Arc<AsyncMutex<RateLimiter>>>,
+    // Retries of the idempotent calls, see `DeribitBuilder::retry_on_overload`
+    pub(crate) max_retries: u32,
+    pub(crate) state: SharedState,
 }
 
 impl DeribitAPIClient {
     pub(crate) fn new(
-        wstx: SplitSink<WSStream, Message>,
-        waiter_tx: mpsc::Sender<(i64, oneshot::Sender<String>)>,
+        wstx: mpsc::Sender<Message>,
+        waiter_tx: mpsc::Sender<(i64, Waiter)>,
+        control_tx: mpsc::Sender<Control>,
         timeout: Duration,
+        default_currency: Option<Currency>,
+        default_instrument: Option<String>,
+        rate_limiter: Option<RateLimiter>,
     ) -> DeribitAPIClient {
         DeribitAPIClient {
             wstx: wstx,
             waiter_tx: waiter_tx,
+            control_tx,
             timeout: timeout,
-            id: 0,
+            closed: false,
+            id: Default::default(),
+            default_currency,
+            default_instrument,
+            auth_expiry: Default::default(),
+            rate_limiter: rate_limiter.map(|limiter| Arc::new(AsyncMutex::new(limiter))),
+            max_retries: 0,
+            state: Default::default(),
         }
     }
 
-    #[throws(Error)]
+    /// Whether the last `AuthRequest` sent through this client succeeded and has not expired yet.
+    pub fn is_authenticated(&self) -> bool {
+        self.auth_expiry().is_some_and(|expiry| expiry > Utc::now())
+    }
+
+    /// When the access token of the last successful `AuthRequest` expires.
+    pub fn auth_expiry(&self) -> Option<DateTime<Utc>> {
+        *self.auth_expiry.lock().unwrap()
+    }
+
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.get()
+    }
+
+    /// The state transitions of the connection from now on, ending after `Closed`.
+    pub fn connection_states(&self) -> impl Stream<Item = ConnectionState> {
+        self.state.watch()
+    }
+
+    pub fn default_currency(&self) -> Option<Currency> {
+        self.default_currency
+    }
+
+    pub fn default_instrument(&self) -> Option<&str> {
+        self.default_instrument.as_deref()
+    }
+
+    #[throws(DeribitError)]
     pub async fn call_raw<'a, R>(&'a mut self, request: R) -> DeribitAPICallRawResult<R::Response>
     where
         R: Request + Serialize + 'a,
     {
-        let (waiter_tx, waiter_rx) = oneshot::channel();
+        self.call_raw_with_timeout(request, self.timeout).await?
+    }
+
+    #[throws(DeribitError)]
+    async fn call_raw_with_timeout<'a, R>(
+        &'a mut self,
+        request: R,
+        timeout: Duration,
+    ) -> DeribitAPICallRawResult<R::Response>
+    where
+        R: Request + Serialize + 'a,
+    {
+        let (payload, waiter_rx) = self.send(request).await?;
+        if self.max_retries > 0 && R::IDEMPOTENT {
+            DeribitAPICallRawResult::new(self.retry(payload, waiter_rx, timeout))
+        } else {
+            DeribitAPICallRawResult::new(response(waiter_rx, timeout))
+        }
+    }
+
+    /// Sends `request`, returning the serialized payload and the receiver of the raw response.
+    #[throws(DeribitError)]
+    async fn send<R>(&mut self, request: R) -> (String, oneshot::Receiver<Result<String>>)
+    where
+        R: Request + Serialize,
+    {
         let req = JSONRPCRequest {
-            id: self.id,
+            id: self.next_id(),
             method: R::METHOD.into(),
             params: request,
         };
-        self.id += 1;
-
         let payload = to_string(&req)?;
+        let waiter_rx = self.send_payload(req.id, payload.clone()).await?;
+        (payload, waiter_rx)
+    }
+
+    fn next_id(&self) -> i64 {
+        self.id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    #[throws(DeribitError)]
+    async fn send_payload(
+        &mut self,
+        id: i64,
+        payload: String,
+    ) -> oneshot::Receiver<Result<String>> {
+        if self.closed {
+            throw!(DeribitError::Closed);
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.lock().await.acquire().await;
+        }
+
+        let (waiter_tx, waiter_rx) = oneshot::channel();
         trace!("[API Client] Request: {}", payload);
         self.wstx.send(Message::Text(payload)).await?;
-        self.waiter_tx.send((req.id, waiter_tx)).await?;
-        DeribitAPICallRawResult::new(waiter_rx, self.timeout)
+        self.waiter_tx.send((id, waiter_tx)).await?;
+        waiter_rx
     }
 
-    #[throws(Error)]
+    /// Waits for the response to `payload`, sending it again while it is rejected with
+    /// `too_many_requests` or lost with the connection, with an exponential backoff.
+    /// The future returned owns the retries, leaving the client free for other calls; each
+    /// re-send waits for the backoff, then for its turn at the client rate limiter.
+    fn retry(
+        &self,
+        payload: String,
+        mut waiter_rx: oneshot::Receiver<Result<String>>,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<String>> + Send + 'static {
+        let mut wstx = self.wstx.clone();
+        let mut waiter_tx = self.waiter_tx.clone();
+        let ids = self.id.clone();
+        let max_retries = self.max_retries;
+        let rate_limiter = self.rate_limiter.clone();
+        async move {
+            let mut attempt = 0;
+            loop {
+                let response = tokio::time::timeout(timeout, waiter_rx)
+                    .await
+                    .map_err(|_| DeribitError::RequestTimeout)?;
+                let transient = match &response {
+                    Ok(Ok(response)) => matches!(
+                        from_str::<Rejection>(response),
+                        Ok(Rejection { error }) if error.code == TOO_MANY_REQUESTS
+                    ),
+                    Ok(Err(_)) => false,
+                    // The connection was lost before the response came
+                    Err(_) => true,
+                };
+                if !transient || attempt == max_retries {
+                    return response?;
+                }
+
+                sleep(backoff(attempt)).await;
+                attempt += 1;
+                trace!("[API Client] Retrying, attempt {}", attempt);
+                if let Some(limiter) = &rate_limiter {
+                    limiter.lock().await.acquire().await;
+                }
+                // Ids are never reused
+                let mut req = from_str::<Value>(&payload)?;
+                let id = ids.fetch_add(1, Ordering::Relaxed);
+                req["id"] = id.into();
+                let (tx, rx) = oneshot::channel();
+                wstx.send(Message::Text(req.to_string())).await?;
+                waiter_tx.send((id, tx)).await?;
+                waiter_rx = rx;
+            }
+        }
+    }
+
+    /// Like `call`, but waits for the response and keeps the exact JSON sent and received along
+    /// with the result, e.g. to attach them to a bug report. A response that fails to parse
+    /// still comes back in the envelope.
+    #[throws(DeribitError)]
+    pub async fn call_debug<R>(&mut self, request: R) -> DebugEnvelope<R::Response>
+    where
+        R: Request + Serialize,
+        R::Response: DeserializeOwned,
+    {
+        let (request, waiter_rx) = self.send(request).await?;
+        let response = timeout(self.timeout, waiter_rx)
+            .await
+            .map_err(|_| DeribitError::RequestTimeout)???;
+        let result = from_str::<JSONRPCResponse<R::Response>>(&response)
+            .map_err(Into::into)
+            .and_then(remote_result);
+        DebugEnvelope {
+            request,
+            response,
+            result,
+        }
+    }
+
+    #[throws(DeribitError)]
     pub async fn call<'a, R>(&'a mut self, request: R) -> DeribitAPICallResult<R::Response>
     where
         R: Request + Serialize + 'a,
@@ -70,24 +276,335 @@ impl DeribitAPIClient {
         let resp: DeribitAPICallRawResult<R::Response> = self.call_raw(request).await?;
         DeribitAPICallResult::new(resp)
     }
+
+    /// Sends all the `requests` at once, each under its own id, returning their results in the
+    /// same order. Responses are routed by id, so they may come back in any order.
+    #[throws(DeribitError)]
+    pub async fn call_many<R>(&mut self, requests: Vec<R>) -> Vec<DeribitAPICallResult<R::Response>>
+    where
+        R: Request + Serialize,
+    {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            results.push(self.call(request).await?);
+        }
+        results
+    }
+
+    /// Like `call`, but fails with `DeribitError::RequestTimeout` after `timeout` instead of the
+    /// client-wide timeout.
+    #[throws(DeribitError)]
+    pub async fn call_with_timeout<'a, R>(
+        &'a mut self,
+        request: R,
+        timeout: Duration,
+    ) -> DeribitAPICallResult<R::Response>
+    where
+        R: Request + Serialize + 'a,
+    {
+        let resp = self.call_raw_with_timeout(request, timeout).await?;
+        DeribitAPICallResult::new(resp)
+    }
+
+    /// Ends the authenticated session. The server closes the connection right after
+    /// instead of answering, which is not an error here. The connection is not reopened, even
+    /// with reconnection on, and calls made afterwards fail with `DeribitError::Closed`.
+    #[throws(DeribitError)]
+    pub async fn logout(&mut self, invalidate_token: bool) {
+        let req = LogoutRequest::invalidate_token(invalidate_token);
+        match self.call(req).await?.await {
+            Ok(()) | Err(DeribitError::CanceledError(_)) => {}
+            Err(e) => throw!(e),
+        }
+        self.closed = true;
+    }
+
+    /// Shuts the connection down gracefully: no call is accepted anymore, those in flight get
+    /// up to `timeout` to be answered, then the connection is closed with a close frame and the
+    /// background task ends, as does the subscription stream. Calls still unanswered by then,
+    /// and those made afterwards, fail with `DeribitError::Closed`.
+    pub async fn close(&mut self, timeout: Duration) {
+        if self.closed {
+            return;
+        }
+        self.closed = true;
+
+        let (done_tx, done_rx) = oneshot::channel();
+        // Fails if the background task already exited, leaving nothing to close
+        if self
+            .control_tx
+            .send(Control::Close(timeout, done_tx))
+            .await
+            .is_ok()
+        {
+            let _ = done_rx.await;
+        }
+        self.state.set(ConnectionState::Closed);
+    }
+
+    /// The number of calls sent and still waiting for their response. Calls given up on, e.g.
+    /// after a timeout, stop counting within a second.
+    #[throws(DeribitError)]
+    pub async fn pending_calls(&mut self) -> usize {
+        let (tx, rx) = oneshot::channel();
+        self.control_tx.send(Control::PendingCalls(tx)).await?;
+        rx.await?
+    }
+
+    /// Round trip time of a websocket ping frame. Unlike a `TestRequest` this
+    /// involves no JSON-RPC processing on the server side.
+    #[throws(DeribitError)]
+    pub async fn ws_ping_rtt(&mut self) -> Duration {
+        if self.closed {
+            throw!(DeribitError::Closed);
+        }
+        let (pong_tx, pong_rx) = oneshot::channel();
+        self.control_tx.send(Control::Ping(pong_tx)).await?;
+        timeout(self.timeout, pong_rx)
+            .await
+            .map_err(|_| DeribitError::RequestTimeout)??
+    }
+
+    /// Names of all the active instruments of `currency`, optionally restricted to `kind`.
+    #[throws(DeribitError)]
+    pub async fn instrument_names(
+        &mut self,
+        currency: Currency,
+        kind: Option<AssetKind>,
+    ) -> Vec<String> {
+        let req = GetInstrumentsRequest {
+            currency: Some(currency),
+            kind,
+            ..Default::default()
+        };
+        let instruments = self.call(req).await?.await?;
+        instruments
+            .iter()
+            .filter(|instrument| instrument.is_active())
+            .map(|instrument| instrument.get_instrument_name().to_string())
+            .collect()
+    }
+
+    /// The legs of a combo instrument, which `get_instruments` does not carry.
+    /// A combo's id is its instrument name.
+    #[throws(DeribitError)]
+    pub async fn combo_legs(&mut self, instrument_name: &str) -> Vec<ComboLeg> {
+        let req = GetComboDetailsRequest::new(instrument_name);
+        self.call(req).await?.await?.legs
+    }
+
+    /// The 24h USD volume of every instrument of `currency`, most traded first.
+    #[throws(DeribitError)]
+    pub async fn instrument_volumes(&mut self, currency: Currency) -> Vec<(String, f64)> {
+        let req = GetBookSummaryByCurrencyRequest::all(currency);
+        let summaries = self.call(req).await?.await?;
+        let mut volumes: Vec<_> = summaries
+            .into_iter()
+            .map(|summary| (summary.instrument_name, summary.volume_usd.unwrap_or(0.)))
+            .collect();
+        volumes.sort_by(|a, b| b.1.total_cmp(&a.1));
+        volumes
+    }
+
+    /// Closes every open position of `currency`, returning the result for each of them.
+    /// Limit closes are placed at the mark price of the position.
+    #[throws(DeribitError)]
+    pub async fn close_all(
+        &mut self,
+        currency: Currency,
+        r#type: ClosePositionType,
+    ) -> Vec<Result<ClosePositionResponse>> {
+        let positions = self.call(GetPositionsRequest::all(currency)).await?.await?;
+
+        let mut results = vec![];
+        for position in positions.iter().filter(|position| position.size() != 0.) {
+            let req = match r#type {
+                ClosePositionType::Market => {
+                    ClosePositionRequest::market(position.instrument_name())
+                }
+                ClosePositionType::Limit => {
+                    ClosePositionRequest::limit(position.instrument_name(), position.mark_price())
+                }
+            };
+            let result = match self.call(req).await {
+                Ok(resp) => resp.await,
+                Err(e) => Err(e),
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    /// The book summaries of all the instruments of the default currency.
+    #[throws(DeribitError)]
+    pub async fn book_summary(&mut self) -> Vec<GetBookSummaryByCurrencyResponse> {
+        let currency = self
+            .default_currency
+            .ok_or(DeribitError::MissingDefault("currency"))?;
+        self.call(GetBookSummaryByCurrencyRequest::all(currency))
+            .await?
+            .await?
+    }
+
+    /// The order book of the default instrument.
+    #[throws(DeribitError)]
+    pub async fn order_book(&mut self) -> GetOrderBookResponse {
+        let instrument_name = self
+            .default_instrument
+            .clone()
+            .ok_or(DeribitError::MissingDefault("instrument"))?;
+        self.call(GetOrderBookRequest::new(&instrument_name))
+            .await?
+            .await?
+    }
+
+    /// Sums the margin each of `orders` would require, buys and sells alike, as a go/no-go check
+    /// before submitting the basket. Margins are totaled per currency they are held in, e.g. USDC
+    /// for linear instruments, as amounts in different currencies cannot be added up.
+    /// Orders without a price are estimated at the instrument's mark price.
+    #[throws(DeribitError)]
+    pub async fn estimate_basket_margin(
+        &mut self,
+        orders: &[Either<BuyRequest, SellRequest>],
+    ) -> BTreeMap<Currency, f64> {
+        let mut margins = BTreeMap::new();
+        for order in orders {
+            let (order, buy) = match order {
+                Either::Left(BuyRequest(order)) => (order, true),
+                Either::Right(SellRequest(order)) => (order, false),
+            };
+            let instrument: InstrumentName = order.instrument_name.parse()?;
+            let currency = instrument.quote_currency.unwrap_or(instrument.currency);
+            let price = match order.price {
+                Some(price) => price,
+                None => {
+                    let req = GetOrderBookRequest::with_depth(&order.instrument_name, 1);
+                    self.call(req).await?.await?.mark_price
+                }
+            };
+            let req = GetMarginsRequest::new(&order.instrument_name, order.amount, price);
+            let margin = self.call(req).await?.await?;
+            *margins.entry(currency).or_insert(0.) += if buy { margin.buy } else { margin.sell };
+        }
+        margins
+    }
+
+    /// Subscribes to the orders, trades, changes and portfolio channels of `currency`
+    /// and turns `subscription` into a stream of the resulting user events.
+    /// The connection needs to be authenticated first.
+    #[throws(DeribitError)]
+    pub async fn subscribe_user(
+        &mut self,
+        currency: Currency,
+        subscription: DeribitSubscriptionClient,
+    ) -> UserStream {
+        let req = PrivateSubscribeRequest::from_channels(&[
+            Channel::UserOrders {
+                kind: None,
+                currency,
+                interval: Interval::Raw,
+            },
+            Channel::UserTrades {
+                kind: None,
+                currency,
+                interval: Interval::Raw,
+            },
+            Channel::UserChanges {
+                kind: None,
+                currency,
+                interval: Interval::Raw,
+            },
+            Channel::UserPortfolio(currency),
+        ]);
+        self.call(req).await?.await?;
+        UserStream::new(subscription)
+    }
+
+    /// Subscribes to the ticker of `instrument_name` and turns `subscription` into a stream of its tickers.
+    #[throws(DeribitError)]
+    pub async fn ticker_stream(
+        &mut self,
+        instrument_name: &str,
+        subscription: DeribitSubscriptionClient,
+    ) -> impl Stream<Item = Result<TickerData>> {
+        let req = PublicSubscribeRequest::from_channels(&[Channel::ticker(
+            instrument_name,
+            Interval::Ms100,
+        )]);
+        self.call(req).await?.await?;
+
+        let instrument_name = instrument_name.to_string();
+        subscription.filter_map(move |message| {
+            let ticker = match message {
+                Ok(SubscriptionMessage {
+                    params: SubscriptionParams::Subscription(SubscriptionData::Ticker(ticker)),
+                    ..
+                }) if ticker.data.instrument_name == instrument_name => Some(Ok(ticker.data)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            };
+            ready(ticker)
+        })
+    }
+
+    /// Subscribes to the trades of `instrument_name` and turns `subscription` into a stream of its trades.
+    #[throws(DeribitError)]
+    pub async fn trades_stream_live(
+        &mut self,
+        instrument_name: &str,
+        subscription: DeribitSubscriptionClient,
+    ) -> impl Stream<Item = Result<PublicTrade>> {
+        let req = PublicSubscribeRequest::from_channels(&[Channel::trades(
+            instrument_name,
+            Interval::Ms100,
+        )]);
+        self.call(req).await?.await?;
+
+        let instrument_name = instrument_name.to_string();
+        subscription.flat_map(move |message| {
+            let trades = match message {
+                Ok(SubscriptionMessage {
+                    params: SubscriptionParams::Subscription(SubscriptionData::Trades(trades)),
+                    ..
+                }) => trades
+                    .data
+                    .into_iter()
+                    .filter(|trade| trade.instrument_name == instrument_name)
+                    .map(Ok)
+                    .collect(),
+                Ok(_) => vec![],
+                Err(e) => vec![Err(e)],
+            };
+            iter(trades)
+        })
+    }
 }
 
 #[pin_project]
 pub struct DeribitAPICallRawResult<R> {
-    #[pin]
-    rx: Timeout<oneshot::Receiver<String>>,
+    rx: BoxFuture<'static, Result<String>>,
     _ty: PhantomData<R>,
 }
 
 impl<R> DeribitAPICallRawResult<R> {
-    pub(crate) fn new(rx: oneshot::Receiver<String>, expiry: Duration) -> Self {
+    pub(crate) fn new<F>(rx: F) -> Self
+    where
+        F: Future<Output = Result<String>> + Send + 'static,
+    {
         DeribitAPICallRawResult {
-            rx: timeout(expiry, rx),
+            rx: rx.boxed(),
             _ty: PhantomData,
         }
     }
 }
 
+/// The raw response of the call waited by `rx`, failing after `expiry`.
+async fn response(rx: oneshot::Receiver<Result<String>>, expiry: Duration) -> Result<String> {
+    timeout(expiry, rx)
+        .await
+        .map_err(|_| DeribitError::RequestTimeout)??
+}
+
 impl<R> Future for DeribitAPICallRawResult<R>
 where
     R: DeserializeOwned,
@@ -95,20 +612,15 @@ where
     type Output = Result<JSONRPCResponse<R>>;
     fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<JSONRPCResponse<R>>> {
         let this = self.project();
-        match this.rx.poll(cx) {
-            Poll::Ready(Ok(ret)) => Poll::Ready(match ret {
-                Ok(resp) => {
-                    let result: StdResult<JSONRPCResponse<R>, _> = from_str(&resp);
-                    if let Err(_) = result.as_ref() {
-                        error!("[API Client] Cannot deserialize RPC response: {}", resp);
-                    }
-                    result.map_err(Into::into)
+        match this.rx.as_mut().poll(cx) {
+            Poll::Ready(Ok(resp)) => {
+                let result: StdResult<JSONRPCResponse<R>, _> = from_str(&resp);
+                if let Err(_) = result.as_ref() {
+                    error!("[API Client] Cannot deserialize RPC response: {}", resp);
                 }
-                Err(err) => Err(err.into()),
-            }),
-            Poll::Ready(Err(Elapsed { .. })) => {
-                Poll::Ready(Err(DeribitError::RequestTimeout.into()))
+                Poll::Ready(result.map_err(Into::into))
             }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
             Poll::Pending => Poll::Pending,
         }
     }
@@ -133,15 +645,49 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<R>> {
         let this = self.project();
         match this.inner.poll(cx) {
-            Poll::Ready(Ok(resp)) => Poll::Ready(resp.result.left_result().map_err(|e| {
-                DeribitError::RemoteError {
-                    code: e.code,
-                    message: e.message,
-                }
-                .into()
-            })),
+            Poll::Ready(Ok(resp)) => Poll::Ready(remote_result(resp)),
             Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
             Poll::Pending => Poll::Pending,
         }
     }
 }
+
+fn remote_result<R>(resp: JSONRPCResponse<R>) -> Result<R> {
+    resp.result
+        .left_result()
+        .map_err(|e| DeribitError::ApiError {
+            code: e.code,
+            message: e.message,
+            data: e.data,
+        })
+}
+
+/// The error code of a request rejected because of the rate limits.
+const TOO_MANY_REQUESTS: i64 = 10028;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// The error answering a request, whatever its result type.
+#[derive(Deserialize)]
+pub(crate) struct Rejection {
+    pub(crate) error: ErrorDetail,
+}
+
+/// Exponential backoff before the retry following `attempt`, with up to 50% of jitter
+/// so that clients throttled together do not come back together.
+fn backoff(attempt: u32) -> Duration {
+    let jitter = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.subsec_nanos());
+    let jitter = f64::from(jitter) / 1e9 / 2.;
+    (RETRY_BASE_DELAY * 2u32.pow(attempt.min(6))).mul_f64(1. + jitter)
+}
+
+/// The outcome of `DeribitAPIClient::call_debug`.
+#[derive(Debug)]
+pub struct DebugEnvelope<R> {
+    /// The JSON-RPC request as sent
+    pub request: String,
+    /// The raw JSON-RPC response as received
+    pub response: String,
+    pub result: Result<R>,
+}
@@ -0,0 +1,90 @@
+use crate::models::{
+    AuthRequest, AuthResponse, Either, JSONRPCFrame, JSONRPCRequest, JSONRPCResponse,
+    LogoutRequest, Request,
+};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde_json::{from_str, to_string};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Share of the token lifetime after which it is refreshed.
+const REFRESH_AT: f64 = 0.8;
+
+/// Keeps the session authenticated by refreshing the access token before it expires,
+/// using the refresh token of the last authentication.
+#[derive(Debug, Default)]
+pub(crate) struct AuthRefresh {
+    // Ids of the auth requests waiting for their response
+    pending: HashSet<i64>,
+    refresh_token: Option<String>,
+    deadline: Option<Instant>,
+    expiry: Arc<Mutex<Option<DateTime<Utc>>>>,
+}
+
+impl AuthRefresh {
+    pub(crate) fn new(expiry: Arc<Mutex<Option<DateTime<Utc>>>>) -> AuthRefresh {
+        AuthRefresh {
+            expiry,
+            ..Default::default()
+        }
+    }
+
+    /// When the token has to be refreshed, if there is one.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub(crate) fn track(&mut self, payload: &str) {
+        let frame = match from_str::<JSONRPCFrame>(payload) {
+            Ok(frame) => frame,
+            Err(_) => return,
+        };
+        match (frame.id, frame.method.as_deref()) {
+            (Some(id), Some(AuthRequest::METHOD)) => {
+                self.pending.insert(id);
+            }
+            (_, Some(LogoutRequest::METHOD)) => {
+                self.refresh_token = None;
+                self.deadline = None;
+                *self.expiry.lock().unwrap() = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Schedules the next refresh if `response` answers an auth request.
+    pub(crate) fn on_response(&mut self, id: i64, response: &str) {
+        if !self.pending.remove(&id) {
+            return;
+        }
+        if let Ok(JSONRPCResponse {
+            result: Either::Left(auth),
+            ..
+        }) = from_str::<JSONRPCResponse<AuthResponse>>(response)
+        {
+            let lifetime = Duration::from_secs(auth.expires_in.max(0) as u64);
+            *self.expiry.lock().unwrap() =
+                Some(Utc::now() + ChronoDuration::seconds(auth.expires_in));
+            self.deadline = Some(Instant::now() + lifetime.mul_f64(REFRESH_AT));
+            self.refresh_token = Some(auth.refresh_token);
+        }
+    }
+
+    /// The refresh request to send under `id`, once the deadline has passed.
+    pub(crate) fn request(&mut self, id: i64) -> Option<String> {
+        self.deadline = None;
+        let refresh_token = self.refresh_token.as_ref()?;
+        let req = JSONRPCRequest {
+            id,
+            method: AuthRequest::METHOD.into(),
+            params: AuthRequest::refresh_token_auth(refresh_token),
+        };
+        let payload = to_string(&req).ok()?;
+        self.pending.insert(id);
+        Some(payload)
+    }
+}
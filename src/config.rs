@@ -0,0 +1,102 @@
+use crate::{errors::DeribitError, models::AuthRequest, DeribitBuilder};
+use fehler::throws;
+use serde::{Deserialize, Serialize};
+use serde_json::from_str;
+use std::{env::var, fs::read_to_string, path::PathBuf, time::Duration};
+
+/// A value that is either given inline or read from an environment variable,
+/// e.g. `"secret": {"env": "DERIBIT_SECRET"}`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Secret {
+    Env { env: String },
+    Value(String),
+}
+
+impl Secret {
+    #[throws(DeribitError)]
+    pub fn resolve(&self) -> String {
+        match self {
+            Secret::Env { env } => {
+                var(env).map_err(|_| DeribitError::MissingEnvironmentVariable(env.clone()))?
+            }
+            Secret::Value(value) => value.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Credentials {
+    pub key: Secret,
+    pub secret: Secret,
+}
+
+/// See `DeribitBuilder::rate_limit`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DeribitConfig {
+    pub testnet: bool,
+    pub subscription_buffer_size: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    pub rate_limit: Option<RateLimitConfig>,
+    pub reconnect: bool,
+    pub max_reconnect_attempts: Option<usize>,
+    pub credentials: Option<Credentials>,
+    /// A JSON file holding the credentials, used when they are not given inline
+    pub credentials_path: Option<PathBuf>,
+}
+
+impl DeribitConfig {
+    /// The credentials given inline or read from `credentials_path`, if any.
+    #[throws(DeribitError)]
+    pub fn credentials(&self) -> Option<Credentials> {
+        match (&self.credentials, &self.credentials_path) {
+            (Some(credentials), _) => Some(credentials.clone()),
+            (None, Some(path)) => Some(from_str(&read_to_string(path)?)?),
+            (None, None) => None,
+        }
+    }
+
+    /// The authentication request built from the configured credentials, if any.
+    #[throws(DeribitError)]
+    pub fn auth_request(&self) -> Option<AuthRequest> {
+        match self.credentials()? {
+            Some(Credentials { key, secret }) => Some(AuthRequest::credential_auth(
+                &key.resolve()?,
+                &secret.resolve()?,
+            )),
+            None => None,
+        }
+    }
+}
+
+impl DeribitBuilder {
+    pub fn from_config(config: &DeribitConfig) -> DeribitBuilder {
+        let mut builder = DeribitBuilder::default();
+        builder.testnet(config.testnet);
+        if let Some(size) = config.subscription_buffer_size {
+            builder.subscription_buffer_size(size);
+        }
+        if let Some(ms) = config.timeout_ms {
+            builder.timeout(Duration::from_millis(ms));
+        }
+        if let Some(RateLimitConfig {
+            requests_per_second,
+            burst,
+        }) = config.rate_limit
+        {
+            builder.rate_limit(requests_per_second, burst);
+        }
+        builder.reconnect(config.reconnect);
+        if let Some(attempts) = config.max_reconnect_attempts {
+            builder.max_reconnect_attempts(attempts);
+        }
+        builder
+    }
+}
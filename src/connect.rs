@@ -0,0 +1,118 @@
+use futures::{pin_mut, select, Future, FutureExt};
+use std::{
+    io::{Error, ErrorKind, Result},
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio::{
+    net::{lookup_host, TcpStream},
+    time::sleep,
+};
+use url::Url;
+
+/// How the TCP connection to the websocket endpoint is established
+/// when the host resolves to several addresses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectStrategy {
+    /// Try the resolved addresses one after another, in resolver order.
+    Sequential,
+    /// Try the first address family, and start racing the other family once `fallback_delay`
+    /// has passed without a connection (RFC 8305).
+    HappyEyeballs { fallback_delay: Duration },
+}
+
+impl Default for ConnectStrategy {
+    fn default() -> Self {
+        ConnectStrategy::HappyEyeballs {
+            fallback_delay: Duration::from_millis(250),
+        }
+    }
+}
+
+impl ConnectStrategy {
+    /// Connects to one of `addrs` with `connect`, following this strategy.
+    pub async fn connect<T, F, Fut>(&self, addrs: &[SocketAddr], connect: F) -> Result<T>
+    where
+        F: Fn(SocketAddr) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        match *self {
+            ConnectStrategy::Sequential => sequential(addrs, &connect).await,
+            ConnectStrategy::HappyEyeballs { fallback_delay } => {
+                happy_eyeballs(addrs, fallback_delay, &connect).await
+            }
+        }
+    }
+}
+
+pub(crate) async fn connect_tcp(url: &Url, strategy: ConnectStrategy) -> Result<TcpStream> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "url has no host"))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "url has no port"))?;
+    // Hosts in IPv6 literal form come bracketed out of the url
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let addrs: Vec<_> = lookup_host((host, port)).await?.collect();
+    let stream = strategy.connect(&addrs, TcpStream::connect).await?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+async fn sequential<T, F, Fut>(addrs: &[SocketAddr], connect: &F) -> Result<T>
+where
+    F: Fn(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut last_error = None;
+    for &addr in addrs {
+        match connect(addr).await {
+            Ok(conn) => return Ok(conn),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| Error::new(ErrorKind::NotFound, "no address to connect to")))
+}
+
+async fn happy_eyeballs<T, F, Fut>(
+    addrs: &[SocketAddr],
+    fallback_delay: Duration,
+    connect: &F,
+) -> Result<T>
+where
+    F: Fn(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let prefer_v6 = addrs.first().map(SocketAddr::is_ipv6).unwrap_or_default();
+    let (primary, secondary): (Vec<_>, Vec<_>) =
+        addrs.iter().partition(|addr| addr.is_ipv6() == prefer_v6);
+
+    let primary = sequential(&primary, connect).fuse();
+    let delay = sleep(fallback_delay).fuse();
+    pin_mut!(primary, delay);
+
+    select! {
+        conn = primary => match conn {
+            Ok(conn) => return Ok(conn),
+            // The primary family failed early, no need to wait for the delay
+            Err(e) if secondary.is_empty() => return Err(e),
+            Err(_) => return sequential(&secondary, connect).await,
+        },
+        _ = delay => {}
+    }
+
+    let secondary = sequential(&secondary, connect).fuse();
+    pin_mut!(secondary);
+    select! {
+        conn = primary => match conn {
+            Ok(conn) => Ok(conn),
+            Err(_) => secondary.await,
+        },
+        conn = secondary => match conn {
+            Ok(conn) => Ok(conn),
+            Err(_) => primary.await,
+        },
+    }
+}
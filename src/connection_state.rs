@@ -0,0 +1,55 @@
+use futures::{channel::mpsc, Stream};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionState {
+    /// `Deribit::connect` was not called yet
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected,
+    /// The connection was lost and is being reopened, see `DeribitBuilder::reconnect`
+    Reconnecting,
+    /// For good: closed by the client, lost without reconnection or given up on
+    Closed,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    state: ConnectionState,
+    watchers: Vec<mpsc::UnboundedSender<ConnectionState>>,
+}
+
+/// The connection state, shared by the builder, the API client and the servo.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedState(Arc<Mutex<Inner>>);
+
+impl SharedState {
+    pub(crate) fn get(&self) -> ConnectionState {
+        self.0.lock().unwrap().state
+    }
+
+    /// Updates the state, telling the watchers if it changed.
+    pub(crate) fn set(&self, state: ConnectionState) {
+        let mut inner = self.0.lock().unwrap();
+        if inner.state != state {
+            inner.state = state;
+            inner
+                .watchers
+                .retain(|watcher| watcher.unbounded_send(state).is_ok());
+        }
+        if state == ConnectionState::Closed {
+            inner.watchers.clear();
+        }
+    }
+
+    /// The transitions from now on, ending once the connection is closed for good.
+    pub(crate) fn watch(&self) -> impl Stream<Item = ConnectionState> {
+        let (tx, rx) = mpsc::unbounded();
+        let mut inner = self.0.lock().unwrap();
+        if inner.state != ConnectionState::Closed {
+            inner.watchers.push(tx);
+        }
+        rx
+    }
+}
@@ -1,37 +1,61 @@
-// use futures::channel::mpsc::SendError;
-// use futures::channel::oneshot::Canceled;
-// use serde_json::Error as JsonError;
-// use std::result::Result as StdResult;
+use futures::channel::mpsc::SendError;
+use futures::channel::oneshot::Canceled;
+use serde_json::{Error as JsonError, Value};
+use std::result::Result as StdResult;
 use thiserror::Error;
-// use tungstenite::error::Error as WebsocketError;
-// use url::ParseError;
+use tungstenite::error::Error as WebsocketError;
+use url::ParseError;
+
+pub type Result<R> = StdResult<R, DeribitError>;
 
-// pub type Result<R> = StdResult<R, DeribitError>;
-pub type Result<T> = anyhow::Result<T>;
 #[derive(Error, Debug)]
 pub enum DeribitError {
-    #[error("Deribit remote error {{code: {code}, message: {message}}}")]
-    RemoteError { code: i64, message: String },
+    /// The JSON-RPC error answering a request, see https://docs.deribit.com/#rpc-error-codes
+    #[error("Deribit API error {{code: {code}, message: {message}}}")]
+    ApiError {
+        code: i64,
+        message: String,
+        data: Option<Value>,
+    },
     #[error("The background servo pulling message exited")]
     ServoExited,
     #[error("Unknown currency {0}")]
     UnknownCurrency(String),
     #[error("Unknown asset kind {0}")]
     UnknownAssetKind(String),
+    #[error("Invalid instrument name {0}")]
+    InvalidInstrumentName(String),
     #[error("Websocket disconnected")]
     WebsocketDisconnected,
+    #[error("Connection closed by the server with code {code}: {reason}")]
+    ConnectionClosed { code: u16, reason: String },
     #[error("Request timed out")]
     RequestTimeout,
-    // #[error("oneshot channel canceled on the other side: {0}")]
-    // CanceledError(#[from] Canceled),
-    // #[error("cannot parse url: {0}")]
-    // ParseError(#[from] ParseError),
-    // #[error("underlying websocket reported an error: {0}")]
-    // WebsocketError(#[from] WebsocketError),
-    // #[error("cannot send message to channel: {0}")]
-    // SendError(#[from] SendError),
-    // #[error("JSON serialization error: {0}")]
-    // JsonError(#[from] JsonError),
-    // #[error("IO error: {0}")]
-    // IOError(#[from] std::io::Error)
+    #[error("The client is closed")]
+    Closed,
+    #[error("Environment variable {0} is not set")]
+    MissingEnvironmentVariable(String),
+    #[error("No default {0} is configured")]
+    MissingDefault(&'static str),
+    #[error("oneshot channel canceled on the other side: {0}")]
+    CanceledError(#[from] Canceled),
+    #[error("cannot parse url: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("underlying websocket reported an error: {0}")]
+    WebsocketError(Box<WebsocketError>),
+    #[error("cannot send message to channel: {0}")]
+    SendError(#[from] SendError),
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] JsonError),
+    #[error("IO error: {0}")]
+    IOError(#[from] std::io::Error),
+    // Errors raised through `anyhow` before everything was folded into `DeribitError`
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<WebsocketError> for DeribitError {
+    fn from(e: WebsocketError) -> DeribitError {
+        DeribitError::WebsocketError(Box::new(e))
+    }
 }
@@ -1,44 +1,101 @@
 #![recursion_limit = "512"]
 
 mod api_client;
+mod auth_refresh;
+mod config;
+pub mod connect;
+mod connection_state;
 pub mod errors;
 mod macros;
 pub mod models;
+mod position_tracker;
+mod rate_limit;
+mod reconnect;
+mod stream_mux;
 mod subscription_client;
+pub mod util;
 
 pub use crate::{
-    api_client::{DeribitAPICallRawResult, DeribitAPICallResult, DeribitAPIClient},
+    api_client::{DebugEnvelope, DeribitAPICallRawResult, DeribitAPICallResult, DeribitAPIClient},
+    config::{Credentials, DeribitConfig, RateLimitConfig, Secret},
+    connect::ConnectStrategy,
+    connection_state::ConnectionState,
     errors::{DeribitError, Result},
-    subscription_client::{DeribitSubscriptionClient, DeribitSubscriptionLimitedClient},
+    position_tracker::PositionTracker,
+    stream_mux::StreamMux,
+    subscription_client::{
+        DeribitSubscriptionClient, DeribitSubscriptionLimitedClient, UserStream,
+    },
 };
 
-use anyhow::Error;
+use crate::{
+    api_client::{Control, Rejection, Waiter},
+    auth_refresh::AuthRefresh,
+    connection_state::SharedState,
+    models::{
+        Currency, HeartbeatType, JSONRPCFrame, JSONRPCRequest, Request, SetHeartbeatRequest,
+        TestRequest,
+    },
+    rate_limit::RateLimiter,
+    reconnect::{Reconnect, Replay},
+};
 use derive_builder::Builder;
-use fehler::throws;
+use fehler::{throw, throws};
 use futures::{
     channel::{mpsc, oneshot},
-    select, FutureExt, SinkExt, Stream, StreamExt, TryStreamExt,
+    future::pending,
+    pin_mut, select, FutureExt, SinkExt, Stream, StreamExt,
 };
-use lazy_static::lazy_static;
 use log::{info, trace, warn};
-use regex::Regex;
+use serde::Deserialize;
+use serde_json::{from_str, to_string};
 use std::{collections::HashMap, time::Duration};
-use tokio::{net::TcpStream, time::timeout};
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio::{
+    net::TcpStream,
+    runtime::Handle,
+    time::{interval, sleep_until, timeout, Instant},
+};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use tungstenite::Message;
 use url::Url;
 
-lazy_static! {
-    static ref RE: Regex = Regex::new(r#""jsonrpc":"2.0","id":(\d+),"#).unwrap();
-}
-
 type WSStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 pub const WS_URL: &'static str = "wss://www.deribit.com/ws/api/v2";
 pub const WS_URL_TESTNET: &'static str = "wss://test.deribit.com/ws/api/v2";
 
+/// How long a graceful close waits for the server to answer the close frame.
+const CLOSE_ACK_TIMEOUT: Duration = Duration::from_secs(1);
+/// How often the waiters of the calls given up on are dropped.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the ids of the calls given up on are remembered to recognize their late responses.
+const ABANDONED_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Deserialize)]
+struct HeartbeatParams {
+    r#type: HeartbeatType,
+}
+
+#[derive(Deserialize)]
+struct Heartbeat {
+    params: HeartbeatParams,
+}
+
+/// Whether the server is checking that the connection is alive, which it is if answered.
+fn is_test_request(frame: &JSONRPCFrame, msg: &str) -> bool {
+    frame.method.as_deref() == Some("heartbeat")
+        && matches!(
+            from_str::<Heartbeat>(msg),
+            Ok(Heartbeat {
+                params: HeartbeatParams {
+                    r#type: HeartbeatType::TestRequest
+                }
+            })
+        )
+}
+
 #[derive(Default, Builder, Debug)]
-#[builder(setter(into))]
+#[builder(setter(into), build_fn(validate = "Self::validate"))]
 pub struct Deribit {
     #[builder(default)]
     testnet: bool,
@@ -46,6 +103,68 @@ pub struct Deribit {
     subscription_buffer_size: usize,
     #[builder(setter(into, strip_option), default)]
     timeout: Option<Duration>,
+    #[builder(setter(into, strip_option), default)]
+    url: Option<String>,
+    #[builder(default)]
+    connect_strategy: ConnectStrategy,
+    /// Currency used by the client helpers that take none, e.g. `DeribitAPIClient::book_summary`
+    #[builder(setter(into, strip_option), default)]
+    default_currency: Option<Currency>,
+    /// Instrument used by the client helpers that take none, e.g. `DeribitAPIClient::order_book`
+    #[builder(setter(into, strip_option), default)]
+    default_instrument: Option<String>,
+    /// Runtime the background task is spawned on, the one `connect` is called from if not set
+    #[builder(setter(into, strip_option), default)]
+    runtime_handle: Option<Handle>,
+    /// Reopen the connection when it is lost, authenticating again and restoring the subscriptions
+    #[builder(default)]
+    reconnect: bool,
+    #[builder(default = "10")]
+    max_reconnect_attempts: usize,
+    #[builder(setter(custom), default)]
+    rate_limit: Option<RateLimiter>,
+    #[builder(setter(custom), default)]
+    max_retries: u32,
+    /// Ask the server for heartbeats every `interval` (10 seconds at least) once connected. Its
+    /// test requests are always answered by the client and never reach the subscription stream
+    #[builder(setter(into, strip_option), default)]
+    auto_heartbeat: Option<Duration>,
+    #[builder(setter(skip))]
+    state: SharedState,
+}
+
+impl DeribitBuilder {
+    /// Throttles `DeribitAPIClient::call` and friends to `requests_per_second` on average, letting
+    /// up to `burst` requests through at once. Calls over the budget wait for their turn instead of
+    /// failing with `too_many_requests`; subscription messages are not affected.
+    ///
+    /// Deribit keeps separate credit pools for the matching engine requests (buy, sell, edit,
+    /// cancel...) and for the rest, while this limiter counts every request against one budget:
+    /// size it after the pool the client draws from the most.
+    ///
+    /// `build` fails unless `requests_per_second` is positive.
+    pub fn rate_limit(&mut self, requests_per_second: f64, burst: u32) -> &mut Self {
+        self.rate_limit = Some(Some(RateLimiter::new(requests_per_second, burst)));
+        self
+    }
+
+    /// Retries the calls rejected with `too_many_requests` or whose response is lost with the
+    /// connection, up to `max_retries` times with an exponential backoff. Only idempotent
+    /// requests are retried: order placements have to be wrapped in `models::Retriable` for that.
+    pub fn retry_on_overload(&mut self, max_retries: u32) -> &mut Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        match &self.rate_limit {
+            Some(Some(limiter)) if limiter.rate().is_nan() || limiter.rate() <= 0. => Err(format!(
+                "the rate limit must be positive, got {}",
+                limiter.rate()
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl Deribit {
@@ -57,75 +176,265 @@ impl Deribit {
         DeribitBuilder::default()
     }
 
-    #[throws(Error)]
+    pub fn ws_url(&self) -> &str {
+        match &self.url {
+            Some(url) => url,
+            None if self.testnet => WS_URL_TESTNET,
+            None => WS_URL,
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Requests per second and burst the calls are throttled to, see `DeribitBuilder::rate_limit`.
+    pub fn rate_limit(&self) -> Option<(f64, u32)> {
+        self.rate_limit
+            .as_ref()
+            .map(|limiter| (limiter.rate(), limiter.burst()))
+    }
+
+    /// How many times a lost connection is reopened in a row, `None` without reconnection.
+    pub fn reconnect_attempts(&self) -> Option<usize> {
+        if self.reconnect {
+            Some(self.max_reconnect_attempts)
+        } else {
+            None
+        }
+    }
+
+    /// `Disconnected` until `connect` is called, the state of the connection it opens afterwards,
+    /// see `DeribitAPIClient::connection_state`.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.get()
+    }
+
+    /// The state transitions of the connection, starting with `Connecting` once `connect` is
+    /// called and ending after `Closed`.
+    pub fn connection_states(&self) -> impl Stream<Item = ConnectionState> {
+        self.state.watch()
+    }
+
+    #[throws(DeribitError)]
     pub async fn connect(self) -> (DeribitAPIClient, DeribitSubscriptionClient) {
-        let ws_url = if self.testnet { WS_URL_TESTNET } else { WS_URL };
         info!("Connecting");
-        let (ws, _) = connect_async(Url::parse(ws_url)?).await?;
-
-        let (wstx, wsrx) = ws.split();
+        self.state.set(ConnectionState::Connecting);
+        let url = Url::parse(self.ws_url())?;
+        let ws = match reconnect::open(&url, self.connect_strategy).await {
+            Ok(ws) => ws,
+            Err(e) => {
+                self.state.set(ConnectionState::Closed);
+                throw!(e)
+            }
+        };
+        self.state.set(ConnectionState::Connected);
+        let reconnect = if self.reconnect {
+            Some(Reconnect {
+                url,
+                connect_strategy: self.connect_strategy,
+                max_attempts: self.max_reconnect_attempts,
+                state: self.state.clone(),
+            })
+        } else {
+            None
+        };
 
         let (stx, srx) = mpsc::channel(self.subscription_buffer_size);
+        let (wstx, wsrx) = mpsc::channel(10);
         let (waiter_tx, waiter_rx) = mpsc::channel(10);
-        let background = Self::servo(wsrx.err_into(), waiter_rx, stx)
-            .inspect(|r| {
+        let (control_tx, control_rx) = mpsc::channel(10);
+        let mut client = DeribitAPIClient::new(
+            wstx,
+            waiter_tx,
+            control_tx,
+            self.timeout.unwrap_or(Duration::from_secs(3600)), // default timeout, 1H
+            self.default_currency,
+            self.default_instrument,
+            self.rate_limit,
+        );
+        client.max_retries = self.max_retries;
+        client.state = self.state.clone();
+        let auth = AuthRefresh::new(client.auth_expiry.clone());
+
+        let mut error_tx = stx.clone();
+        let state = self.state.clone();
+        let background = Self::servo(ws, wsrx, waiter_rx, control_rx, stx, reconnect, auth).then(
+            |r| async move {
+                state.set(ConnectionState::Closed);
                 if let Err(e) = r {
-                    warn!("[Servo] Exiting because of '{}'", e)
+                    warn!("[Servo] Exiting because of '{}'", e);
+                    // Let the subscription stream tell why it ends
+                    let _ = error_tx.send(Err(e)).await;
                 }
-            })
-            .then(|_| async { () });
+            },
+        );
 
-        tokio::spawn(background);
+        match &self.runtime_handle {
+            Some(handle) => handle.spawn(background),
+            None => tokio::spawn(background),
+        };
 
-        (
-            DeribitAPIClient::new(
-                wstx,
-                waiter_tx,
-                self.timeout.unwrap_or(Duration::from_secs(3600)), // default timeout, 1H
-            ),
-            DeribitSubscriptionClient::new(srx),
-        )
+        if let Some(interval) = self.auto_heartbeat {
+            let req = SetHeartbeatRequest::with_interval(interval.as_secs());
+            client.call(req).await?.await?;
+        }
+
+        (client, DeribitSubscriptionClient::new(srx))
     }
 
-    #[throws(Error)]
+    #[throws(DeribitError)]
     async fn servo(
-        ws: impl Stream<Item = Result<Message>> + Unpin,
-        mut waiter_rx: mpsc::Receiver<(i64, oneshot::Sender<String>)>,
-        mut stx: mpsc::Sender<String>,
+        ws: WSStream,
+        mut out_rx: mpsc::Receiver<Message>,
+        mut waiter_rx: mpsc::Receiver<(i64, Waiter)>,
+        mut control_rx: mpsc::Receiver<Control>,
+        mut stx: mpsc::Sender<Result<String>>,
+        reconnect: Option<Reconnect>,
+        mut auth: AuthRefresh,
     ) {
-        let mut ws = ws.fuse();
-        let mut waiters: HashMap<i64, oneshot::Sender<String>> = HashMap::new();
+        let (mut wstx, wsrx) = ws.split();
+        let mut ws = wsrx.fuse();
+        let mut replay = Replay::default();
+        let mut waiters: HashMap<i64, Waiter> = HashMap::new();
+        // The pings in flight by payload, with when they were sent
+        let mut pings: HashMap<Vec<u8>, (Instant, oneshot::Sender<Duration>)> = HashMap::new();
+        let mut ping_id: u64 = 0;
 
         let mut orphan_messages = HashMap::new();
+        // Requests whose caller gave up waiting, e.g. after a timeout, with when that was noticed
+        let mut abandoned: HashMap<i64, Instant> = HashMap::new();
+        let mut sweep = interval(SWEEP_INTERVAL);
+
+        // Code and reason of the close frame sent by the server, if any
+        let mut closed = None;
+        // Deadline for the calls in flight and who to tell once the connection is closed
+        let mut closing: Option<(Instant, oneshot::Sender<()>)> = None;
 
         let (mut sdropped, mut cdropped) = (false, false);
         while !(sdropped && cdropped) {
+            if let Some((drain_deadline, _)) = &closing {
+                // The waiters of the last calls may still be queued
+                while let Ok((id, waiter)) = waiter_rx.try_recv() {
+                    Self::register_waiter(&mut waiters, &mut orphan_messages, id, waiter);
+                }
+                waiters.retain(|_, waiter| !waiter.is_canceled());
+                if waiters.is_empty() || Instant::now() >= *drain_deadline {
+                    break;
+                }
+            }
+
+            let drain_deadline = closing.as_ref().map(|(drain_deadline, _)| *drain_deadline);
+            let drain = async move {
+                match drain_deadline {
+                    Some(drain_deadline) => sleep_until(drain_deadline).await,
+                    None => pending().await,
+                }
+            }
+            .fuse();
+            pin_mut!(drain);
+
+            let deadline = auth.deadline();
+            let refresh = async move {
+                match deadline {
+                    Some(deadline) => sleep_until(deadline).await,
+                    None => pending().await,
+                }
+            }
+            .fuse();
+            pin_mut!(refresh);
+
+            let tick = sweep.tick().fuse();
+            pin_mut!(tick);
+
             select! {
                 msg = ws.next() => {
                     trace!("[Servo] Message: {:?}", msg);
                     if sdropped { continue; }
-                    let msg = if let Some(msg) = msg { msg } else { Err(DeribitError::WebsocketDisconnected)? };
+                    let msg = match msg {
+                        Some(Ok(msg)) => msg,
+                        lost => {
+                            let e = match (lost, closed.take()) {
+                                (_, Some((code, reason))) => DeribitError::ConnectionClosed { code, reason },
+                                (Some(Err(e)), None) => e.into(),
+                                _ => DeribitError::WebsocketDisconnected,
+                            };
+                            if closing.is_some() {
+                                warn!("[Servo] Connection lost while closing because of '{}'", e);
+                                break;
+                            }
+                            if replay.logged_out() {
+                                info!("[Servo] Connection closed after logout");
+                                break;
+                            }
+                            let reconnect = match &reconnect {
+                                Some(reconnect) => reconnect,
+                                None => throw!(e),
+                            };
+                            warn!("[Servo] Connection lost because of '{}', reconnecting", e);
+                            // The responses to the requests in flight are lost with the connection,
+                            // those already received are still waiting for their waiter. The waiters
+                            // still queued belong to requests written to the lost connection too
+                            while let Ok((id, waiter)) = waiter_rx.try_recv() {
+                                Self::register_waiter(&mut waiters, &mut orphan_messages, id, waiter);
+                            }
+                            waiters.clear();
+                            pings.clear();
+                            abandoned.clear();
+                            let requests = replay.requests(&mut auth);
+                            let (tx, rx) = reconnect.reconnect(&requests).await?.split();
+                            wstx = tx;
+                            ws = rx.fuse();
+                            info!("[Servo] Reconnected");
+                            continue;
+                        }
+                    };
 
-                    match msg? {
+                    match msg {
                         Message::Text(msg) => {
-                            if let Some(cap) = RE.captures(&msg) { // TODO: If deribit returns unordered keys, then this will fail.
+                            let frame = from_str::<JSONRPCFrame>(&msg).ok();
+                            if let Some(id) = frame.as_ref().and_then(|frame| frame.response_id()) {
+                                auth.on_response(id, &msg);
+                                if id < 0 {
+                                    // is the response to a request sent by the servo itself, its
+                                    // failure is reported to the subscriber since nobody else waits for it
+                                    if let Ok(Rejection { error: e }) = from_str::<Rejection>(&msg) {
+                                        warn!("[Servo] Replayed request failed: {}", msg);
+                                        let e = DeribitError::ApiError { code: e.code, message: e.message, data: e.data };
+                                        let _ = timeout(Duration::from_millis(1), stx.send(Err(e))).await;
+                                    }
+                                    continue;
+                                }
                                 // is a API call response
-                                let id_str = cap.get(1).expect("No captured group in a capture result, this cannot happen").as_str();
-                                let id = id_str.parse().expect("Cannot parse integer while it is deemed as integer by regex, this cannot happen");
                                 let waiter = match waiters.remove(&id) {
                                     Some(waiter) => waiter,
+                                    None if abandoned.remove(&id).is_some() => {
+                                        info!("[Servo] Late response to abandoned request {}", id);
+                                        continue;
+                                    }
                                     None => {
                                         orphan_messages.insert(id, msg);
                                         continue;
                                     }
                                 };
 
-                                if let Err(msg) = waiter.send(msg) {
+                                if let Err(msg) = waiter.send(Ok(msg)) {
                                     info!("[Servo] Orphan response: {:?}", msg);
                                 }
+                            } else if frame.is_some_and(|frame| is_test_request(&frame, &msg)) {
+                                // The server drops the connection if the test request goes unanswered
+                                trace!("[Servo] Answering a heartbeat test request");
+                                let req = JSONRPCRequest {
+                                    id: replay.next_id(),
+                                    method: TestRequest::METHOD.into(),
+                                    params: TestRequest::new(),
+                                };
+                                if let Err(e) = wstx.send(Message::Text(to_string(&req)?)).await {
+                                    warn!("[Servo] Cannot answer the heartbeat because of '{}'", e);
+                                }
                             } else {
                                 // is a subscription messasge
-                                let fut = stx.send(msg);
+                                let fut = stx.send(Ok(msg));
                                 let fut = timeout(Duration::from_millis(1),fut, );
                                 match fut.await {
                                     Ok(Ok(_)) => {}
@@ -138,8 +447,12 @@ impl Deribit {
                         Message::Ping(_) => {
                             trace!("[Servo] Received Ping");
                         }
-                        Message::Pong(_) => {
-                            trace!("[Servo] Received Ping");
+                        Message::Pong(payload) => {
+                            trace!("[Servo] Received Pong");
+                            // Unsolicited pongs match no ping
+                            if let Some((start, waiter)) = pings.remove(&payload) {
+                                let _ = waiter.send(start.elapsed());
+                            }
                         }
                         Message::Binary(_) => {
                             trace!("[Servo] Received Binary");
@@ -147,30 +460,128 @@ impl Deribit {
                         Message::Frame(_) => {
                             trace!("[Servo] Received Frame");
                         }
-                        Message::Close(_) => {
-                            trace!("[Servo] Received Close");
+                        Message::Close(frame) => {
+                            trace!("[Servo] Received Close: {:?}", frame);
+                            // 1005 stands for a close frame without status code
+                            closed = Some(frame.map_or((1005, String::new()), |frame| {
+                                (frame.code.into(), frame.reason.into_owned())
+                            }));
                         }
                     }
                 }
-                waiter = waiter_rx.next() => {
-                    if let Some((id, waiter)) = waiter {
-                        if orphan_messages.contains_key(&id) {
-                            info!("[Servo] Message come before waiter");
-                            let msg = orphan_messages.remove(&id).unwrap();
-                            if let Err(msg) = waiter.send(msg) {
-                                info!("[Servo] The client for request {} is dropped, response is {:?}", id, msg);
+                msg = out_rx.next() => {
+                    if let Some(msg) = msg {
+                        if let Message::Text(payload) = &msg {
+                            replay.track(payload);
+                            auth.track(payload);
+                        }
+                        if let Err(e) = wstx.send(msg).await {
+                            // With reconnection on, the read side notices the loss and recovers
+                            if reconnect.is_none() {
+                                throw!(e)
                             }
-                        } else {
-                            waiters.insert(id, waiter);
+                            warn!("[Servo] Cannot send message because of '{}'", e);
+                        }
+                    }
+                }
+                _ = drain => {} // The calls in flight ran out of time
+                now = tick => {
+                    waiters.retain(|&id, waiter| {
+                        let canceled = waiter.is_canceled();
+                        if canceled {
+                            abandoned.insert(id, now);
+                        }
+                        !canceled
+                    });
+                    abandoned.retain(|_, since| now.duration_since(*since) < ABANDONED_TTL);
+                }
+                _ = refresh => {
+                    if let Some(request) = auth.request(replay.next_id()) {
+                        trace!("[Servo] Refreshing the access token");
+                        if let Err(e) = wstx.send(Message::Text(request)).await {
+                            warn!("[Servo] Cannot refresh the access token because of '{}'", e);
                         }
+                    }
+                }
+                waiter = waiter_rx.next() => {
+                    if let Some((id, waiter)) = waiter {
+                        Self::register_waiter(&mut waiters, &mut orphan_messages, id, waiter);
                     } else {
                         cdropped = true;
                         info!("[Servo] API Client dropped");
                     }
                 }
+                control = control_rx.next() => {
+                    match control {
+                        Some(Control::PendingCalls(waiter)) => {
+                            // The waiters of the last calls may still be queued
+                            while let Ok((id, waiter)) = waiter_rx.try_recv() {
+                                Self::register_waiter(&mut waiters, &mut orphan_messages, id, waiter);
+                            }
+                            let _ = waiter.send(waiters.len());
+                        }
+                        Some(Control::Ping(waiter)) => {
+                            ping_id += 1;
+                            let payload = ping_id.to_be_bytes().to_vec();
+                            match wstx.send(Message::Ping(payload.clone())).await {
+                                Ok(()) => {
+                                    pings.insert(payload, (Instant::now(), waiter));
+                                }
+                                Err(e) => warn!("[Servo] Cannot send ping because of '{}'", e),
+                            }
+                        }
+                        Some(Control::Close(drain, done)) => {
+                            info!("[Servo] Closing once the calls in flight are answered");
+                            closing = Some((Instant::now() + drain, done));
+                        }
+                        None => {}
+                    }
+                }
+            };
+        }
+
+        if let Some((_, done)) = closing {
+            // Calls still waiting will never be answered
+            for (_, waiter) in waiters.drain() {
+                let _ = waiter.send(Err(DeribitError::Closed));
+            }
+            if let Err(e) = wstx.close().await {
+                warn!("[Servo] Cannot send the close frame because of '{}'", e);
+            }
+            // Wait for the server to acknowledge the close frame
+            let acknowledged = async {
+                while let Some(Ok(msg)) = ws.next().await {
+                    if msg.is_close() {
+                        break;
+                    }
+                }
             };
+            let _ = timeout(CLOSE_ACK_TIMEOUT, acknowledged).await;
+            info!("[Servo] Connection closed");
+            let _ = done.send(());
+            return;
         }
         info!("Servo exit with all receiver dropped");
         // Exit with all receiver dropped
     }
+
+    /// Hands the response to `waiter` if it already arrived, keeps the waiter for it otherwise.
+    fn register_waiter(
+        waiters: &mut HashMap<i64, Waiter>,
+        orphan_messages: &mut HashMap<i64, String>,
+        id: i64,
+        waiter: Waiter,
+    ) {
+        if let Some(msg) = orphan_messages.remove(&id) {
+            info!("[Servo] Message come before waiter");
+            if let Err(msg) = waiter.send(Ok(msg)) {
+                info!(
+                    "[Servo] The client for request {} is dropped, response is {:?}",
+                    id, msg
+                );
+            }
+        } else {
+            waiters.insert(id, waiter);
+        }
+    }
 }
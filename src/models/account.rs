@@ -1,6 +1,13 @@
-use crate::models::{AssetKind, Currency, Direction};
+use crate::{
+    models::{
+        market_data::ComboLeg, AssetKind, Currency, Direction, InstrumentName, Moneyness,
+        OptionType, Request,
+    },
+    util,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 crate::define_request! {
     Name => GetPositions;
@@ -14,28 +21,45 @@ crate::define_request! {
 }
 
 impl GetPositionsRequest {
-    pub fn all(currency: String) -> Self {
+    pub fn all<C: Into<String>>(currency: C) -> Self {
         Self {
-            currency,
+            currency: currency.into(),
             kind: None,
         }
     }
 
-    pub fn futures(currency: String) -> Self {
+    pub fn futures<C: Into<String>>(currency: C) -> Self {
         Self {
-            currency,
+            currency: currency.into(),
             kind: Some(AssetKind::Future),
         }
     }
 
-    pub fn options(currency: String) -> Self {
+    pub fn options<C: Into<String>>(currency: C) -> Self {
         Self {
-            currency,
+            currency: currency.into(),
             kind: Some(AssetKind::Option),
         }
     }
 }
 
+crate::define_request! {
+    Name => GetPosition;
+    Method => "private/get_position";
+    Request => {
+        pub instrument_name: String,
+    };
+    Response => GetPositionsResponse;
+}
+
+impl GetPositionRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "snake_case", tag = "kind")]
 pub enum GetPositionsResponse {
@@ -80,6 +104,216 @@ pub enum GetPositionsResponse {
         total_profit_loss: f64,
         vega: f64,
     },
+    FutureCombo {
+        average_price: f64,
+        delta: f64,
+        direction: Direction,
+        floating_profit_loss: f64,
+        index_price: f64,
+        initial_margin: f64,
+        instrument_name: String,
+        maintenance_margin: f64,
+        mark_price: f64,
+        realized_profit_loss: f64,
+        size: f64,
+        total_profit_loss: f64,
+    },
+    OptionCombo {
+        average_price: f64,
+        delta: f64,
+        direction: Direction,
+        floating_profit_loss: f64,
+        index_price: f64,
+        initial_margin: f64,
+        instrument_name: String,
+        maintenance_margin: f64,
+        mark_price: f64,
+        realized_profit_loss: f64,
+        size: f64,
+        total_profit_loss: f64,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LegExposure {
+    pub instrument_name: String,
+    pub signed_amount: f64,
+}
+
+/// Values a profit or loss of an inverse (coin margined) instrument in USD.
+pub fn coin_pnl_to_usd(pnl_coin: f64, index_price: f64) -> f64 {
+    pnl_coin * index_price
+}
+
+/// Options whose strike is within this fraction of the index price are considered at the money.
+pub const ATM_THRESHOLD: f64 = 0.005;
+
+impl GetPositionsResponse {
+    pub fn instrument_name(&self) -> &str {
+        match self {
+            GetPositionsResponse::Future {
+                instrument_name, ..
+            } => instrument_name,
+            GetPositionsResponse::Option {
+                instrument_name, ..
+            } => instrument_name,
+            GetPositionsResponse::FutureCombo {
+                instrument_name, ..
+            } => instrument_name,
+            GetPositionsResponse::OptionCombo {
+                instrument_name, ..
+            } => instrument_name,
+        }
+    }
+
+    pub fn size(&self) -> f64 {
+        match self {
+            GetPositionsResponse::Future { size, .. } => *size,
+            GetPositionsResponse::Option { size, .. } => *size,
+            GetPositionsResponse::FutureCombo { size, .. } => *size,
+            GetPositionsResponse::OptionCombo { size, .. } => *size,
+        }
+    }
+
+    pub fn mark_price(&self) -> f64 {
+        match self {
+            GetPositionsResponse::Future { mark_price, .. } => *mark_price,
+            GetPositionsResponse::Option { mark_price, .. } => *mark_price,
+            GetPositionsResponse::FutureCombo { mark_price, .. } => *mark_price,
+            GetPositionsResponse::OptionCombo { mark_price, .. } => *mark_price,
+        }
+    }
+
+    pub fn index_price(&self) -> f64 {
+        match self {
+            GetPositionsResponse::Future { index_price, .. } => *index_price,
+            GetPositionsResponse::Option { index_price, .. } => *index_price,
+            GetPositionsResponse::FutureCombo { index_price, .. } => *index_price,
+            GetPositionsResponse::OptionCombo { index_price, .. } => *index_price,
+        }
+    }
+
+    /// Whether the instrument is linear, e.g. `BTC_USDC-PERPETUAL`, making profits and losses
+    /// in its stablecoin quote currency rather than in coin.
+    fn is_linear(&self) -> bool {
+        self.instrument_name()
+            .parse::<InstrumentName>()
+            .is_ok_and(|name| name.quote_currency.is_some())
+    }
+
+    /// Values a profit or loss of the position in USD, converting it at the current index price
+    /// unless the instrument is linear.
+    fn pl_usd(&self, pl: f64) -> f64 {
+        if self.is_linear() {
+            pl
+        } else {
+            coin_pnl_to_usd(pl, self.index_price())
+        }
+    }
+
+    /// The total profit or loss in USD, converted at the current index price for inverse instruments.
+    pub fn total_pl_usd(&self) -> f64 {
+        let total_profit_loss = match self {
+            GetPositionsResponse::Future {
+                total_profit_loss, ..
+            } => total_profit_loss,
+            GetPositionsResponse::Option {
+                total_profit_loss, ..
+            } => total_profit_loss,
+            GetPositionsResponse::FutureCombo {
+                total_profit_loss, ..
+            } => total_profit_loss,
+            GetPositionsResponse::OptionCombo {
+                total_profit_loss, ..
+            } => total_profit_loss,
+        };
+        self.pl_usd(*total_profit_loss)
+    }
+
+    /// The floating profit or loss in USD, as reported by Deribit for options
+    /// and converted at the current index price for inverse instruments otherwise.
+    pub fn floating_pl_usd(&self) -> f64 {
+        let floating_profit_loss = match self {
+            GetPositionsResponse::Option {
+                floating_profit_loss_usd,
+                ..
+            } => return *floating_profit_loss_usd,
+            GetPositionsResponse::Future {
+                floating_profit_loss,
+                ..
+            } => floating_profit_loss,
+            GetPositionsResponse::FutureCombo {
+                floating_profit_loss,
+                ..
+            } => floating_profit_loss,
+            GetPositionsResponse::OptionCombo {
+                floating_profit_loss,
+                ..
+            } => floating_profit_loss,
+        };
+        self.pl_usd(*floating_profit_loss)
+    }
+
+    /// The underlying price at which the option pays back its premium, `None` for futures.
+    pub fn breakeven(&self) -> Option<f64> {
+        match self {
+            GetPositionsResponse::Option {
+                average_price_usd,
+                instrument_name,
+                ..
+            } => {
+                let strike = util::strike(instrument_name)?;
+                match util::option_type(instrument_name)? {
+                    OptionType::Call => Some(strike + average_price_usd),
+                    OptionType::Put => Some(strike - average_price_usd),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Spreads the size of a combo position over the `legs` of the combo,
+    /// `None` if this is not a combo position.
+    pub fn leg_exposures(&self, legs: &[ComboLeg]) -> Option<Vec<LegExposure>> {
+        match self {
+            GetPositionsResponse::FutureCombo { size, .. }
+            | GetPositionsResponse::OptionCombo { size, .. } => Some(
+                legs.iter()
+                    .map(|leg| LegExposure {
+                        instrument_name: leg.instrument_name.clone(),
+                        signed_amount: size * leg.amount as f64,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The moneyness of the option against the current index price, `None` for futures.
+    pub fn moneyness(&self) -> Option<Moneyness> {
+        match self {
+            GetPositionsResponse::Option {
+                index_price,
+                instrument_name,
+                ..
+            } => {
+                let strike = util::strike(instrument_name)?;
+                if (index_price - strike).abs() <= strike * ATM_THRESHOLD {
+                    return Some(Moneyness::ATM);
+                }
+                let in_the_money = match util::option_type(instrument_name)? {
+                    OptionType::Call => *index_price > strike,
+                    OptionType::Put => *index_price < strike,
+                };
+                if in_the_money {
+                    Some(Moneyness::ITM)
+                } else {
+                    Some(Moneyness::OTM)
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 crate::define_request! {
@@ -124,9 +358,33 @@ crate::define_request! {
         pub projected_initial_margin: Option<f64>,
         pub deposit_address: Option<String>,
         pub referrer_id: Option<String>,
+        pub fees: Option<Vec<FeeSchedule>>,
     };
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeType {
+    Fixed,
+    Relative,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FeeSchedule {
+    pub currency: Currency,
+    pub instrument_type: String,
+    pub fee_type: FeeType,
+    pub maker_fee: f64,
+    pub taker_fee: f64,
+}
+
+impl GetAccountSummaryResponse {
+    /// The maker/taker fees per instrument type, only returned by an extended summary.
+    pub fn fee_tier(&self) -> &[FeeSchedule] {
+        self.fees.as_deref().unwrap_or_default()
+    }
+}
+
 impl GetAccountSummaryRequest {
     pub fn abridged(currency: Currency) -> Self {
         Self {
@@ -142,6 +400,57 @@ impl GetAccountSummaryRequest {
     }
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetTransactionLogRequest {
+    pub currency: Currency,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<u64>,
+}
+
+impl GetTransactionLogRequest {
+    pub fn new(currency: Currency, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            currency,
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            ..Default::default()
+        }
+    }
+}
+
+/// Pass `continuation` back in the request to get the next page, `None` on the last page.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetTransactionLogResponse {
+    pub logs: Vec<TransactionLogEntry>,
+    pub continuation: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TransactionLogEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub r#type: String,
+    pub amount: Option<f64>,
+    pub balance: f64,
+    pub change: f64,
+    pub cashflow: f64,
+    pub currency: Currency,
+    pub instrument_name: Option<String>,
+    pub trade_id: Option<String>,
+    pub order_id: Option<String>,
+}
+
+impl Request for GetTransactionLogRequest {
+    const METHOD: &'static str = "private/get_transaction_log";
+    type Response = GetTransactionLogResponse;
+}
+
 crate::define_request! {
     Name => GetSubaccounts;
     Method => "private/get_subaccounts";
@@ -184,12 +493,97 @@ pub struct GetSubaccountsResponse {
     pub is_password: bool,
     pub login_enabled: bool,
     pub not_confirmed_email: Option<String>,
-    pub portfolio: HashMap<Currency, Portfolio>,
+    pub portfolio: BTreeMap<Currency, Portfolio>,
     pub receive_notifications: bool,
     pub system_name: String,
     pub tfa_enabled: Option<bool>,
     pub r#type: String,
     pub username: String,
+    // Not sent back on creation
+    #[serde(default)]
     pub referrals_count: u64,
+    #[serde(default)]
     pub security_keys_enabled: bool,
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct CreateSubaccountRequest;
+
+impl CreateSubaccountRequest {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Request for CreateSubaccountRequest {
+    const METHOD: &'static str = "private/create_subaccount";
+    const IDEMPOTENT: bool = false;
+    const HAS_PAYLOAD: bool = false;
+    type Response = GetSubaccountsResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChangeSubaccountNameRequest {
+    pub sid: u64,
+    pub name: String,
+}
+
+impl ChangeSubaccountNameRequest {
+    pub fn new(sid: u64, name: &str) -> Self {
+        Self {
+            sid,
+            name: name.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSubaccountNameResponse {
+    Ok,
+}
+
+impl Request for ChangeSubaccountNameRequest {
+    const METHOD: &'static str = "private/change_subaccount_name";
+    type Response = ChangeSubaccountNameResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubaccountLoginState {
+    Enable,
+    Disable,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ToggleSubaccountLoginRequest {
+    pub sid: u64,
+    pub state: SubaccountLoginState,
+}
+
+impl ToggleSubaccountLoginRequest {
+    pub fn enable(sid: u64) -> Self {
+        Self {
+            sid,
+            state: SubaccountLoginState::Enable,
+        }
+    }
+
+    pub fn disable(sid: u64) -> Self {
+        Self {
+            sid,
+            state: SubaccountLoginState::Disable,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ToggleSubaccountLoginResponse {
+    Ok,
+}
+
+impl Request for ToggleSubaccountLoginRequest {
+    const METHOD: &'static str = "private/toggle_subaccount_login";
+    type Response = ToggleSubaccountLoginResponse;
+}
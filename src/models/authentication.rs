@@ -1,7 +1,9 @@
 use crate::models::Request;
+use chrono::{DateTime, Utc};
+use ring::hmac;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum GrantType {
     Password,
@@ -26,7 +28,7 @@ pub struct AuthRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refresh_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub timestamp: Option<String>,
+    pub timestamp: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -49,20 +51,34 @@ impl AuthRequest {
         }
     }
 
-    pub fn signature_auth(
+    /// Authenticates by signing the current time, `nonce` and `data` with `secret`,
+    /// which is never sent.
+    pub fn signature_auth(id: &str, secret: &str, nonce: &str, data: &str) -> AuthRequest {
+        Self::signature_auth_at(id, secret, Utc::now(), nonce, data)
+    }
+
+    pub fn signature_auth_at(
         id: &str,
-        timestamp: &str,
-        signature: &str,
-        nonce: Option<&str>,
-        data: Option<&str>,
+        secret: &str,
+        timestamp: DateTime<Utc>,
+        nonce: &str,
+        data: &str,
     ) -> AuthRequest {
+        let timestamp = timestamp.timestamp_millis() as u64;
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        let tag = hmac::sign(
+            &key,
+            format!("{}\n{}\n{}", timestamp, nonce, data).as_bytes(),
+        );
+        let signature = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+
         AuthRequest {
             grant_type: GrantType::ClientSignature,
             client_id: Some(id.into()),
-            timestamp: Some(timestamp.into()),
-            signature: Some(signature.into()),
-            nonce: nonce.map(|x| x.into()),
-            data: data.map(|x| x.into()),
+            timestamp: Some(timestamp),
+            signature: Some(signature),
+            nonce: Some(nonce.into()),
+            data: Some(data.into()).filter(|data: &String| !data.is_empty()),
             ..Default::default()
         }
     }
@@ -78,16 +94,17 @@ impl AuthRequest {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct AuthResponse {
-    acccess_token: Option<String>,
-    expires_in: i64,
-    refresh_token: String,
-    scope: String,
-    state: Option<String>,
-    token_type: String,
+    pub access_token: Option<String>,
+    pub expires_in: i64,
+    pub refresh_token: String,
+    pub scope: String,
+    pub state: Option<String>,
+    pub token_type: String,
 }
 
 impl Request for AuthRequest {
     const METHOD: &'static str = "public/auth";
+    const IDEMPOTENT: bool = false;
     type Response = AuthResponse;
 }
 
@@ -99,6 +116,7 @@ pub struct ExchangeTokenRequest {
 
 impl Request for ExchangeTokenRequest {
     const METHOD: &'static str = "public/exchange_token";
+    const IDEMPOTENT: bool = false;
     type Response = AuthResponse;
 }
 
@@ -110,14 +128,31 @@ pub struct ForkTokenRequest {
 
 impl Request for ForkTokenRequest {
     const METHOD: &'static str = "public/fork_token";
+    const IDEMPOTENT: bool = false;
     type Response = AuthResponse;
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct LogoutRequest;
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct LogoutRequest {
+    /// Whether to invalidate all the tokens of the session, true by default on the server side
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalidate_token: Option<bool>,
+}
+
+impl LogoutRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn invalidate_token(invalidate_token: bool) -> Self {
+        Self {
+            invalidate_token: Some(invalidate_token),
+        }
+    }
+}
 
 impl Request for LogoutRequest {
     const METHOD: &'static str = "private/logout";
-    const HAS_PAYLOAD: bool = false;
+    const IDEMPOTENT: bool = false;
     type Response = ();
 }
@@ -0,0 +1,119 @@
+use crate::{
+    errors::DeribitError,
+    models::{Currency, OptionType},
+};
+use chrono::{Datelike, NaiveDate};
+use fehler::{throw, throws};
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    result::Result as StdResult,
+    str::FromStr,
+};
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InstrumentKind {
+    Perpetual,
+    Future {
+        expiry: NaiveDate,
+    },
+    Option {
+        expiry: NaiveDate,
+        strike: f64,
+        option_type: OptionType,
+    },
+}
+
+/// A parsed instrument name, e.g. `BTC-PERPETUAL`, `BTC-28JUN19` or `BTC-28JUN19-9000-C`.
+/// Linear instruments carry their quote currency, e.g. `BTC_USDC-PERPETUAL`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstrumentName {
+    pub currency: Currency,
+    pub quote_currency: Option<Currency>,
+    pub kind: InstrumentKind,
+}
+
+fn parse_expiry(expiry: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(expiry, "%d%b%y").ok()
+}
+
+impl FromStr for InstrumentName {
+    type Err = DeribitError;
+
+    #[throws(DeribitError)]
+    fn from_str(name: &str) -> InstrumentName {
+        let invalid = || DeribitError::InvalidInstrumentName(name.to_string());
+
+        let segments: Vec<_> = name.split('-').collect();
+        let (underlying, rest) = match segments.split_first() {
+            Some((underlying, rest)) if !rest.is_empty() => (*underlying, rest),
+            _ => throw!(invalid()),
+        };
+        let (currency, quote_currency) = match underlying.split_once('_') {
+            Some((currency, quote)) => (currency, Some(quote.parse().map_err(|_| invalid())?)),
+            None => (underlying, None),
+        };
+        let currency = currency.parse().map_err(|_| invalid())?;
+
+        let kind = match rest {
+            ["PERPETUAL"] => InstrumentKind::Perpetual,
+            [expiry] => InstrumentKind::Future {
+                expiry: parse_expiry(expiry).ok_or_else(invalid)?,
+            },
+            [expiry, strike, option_type] => InstrumentKind::Option {
+                expiry: parse_expiry(expiry).ok_or_else(invalid)?,
+                // Fractional strikes are written with a "d", e.g. XRP_USDC-30AUG24-0d625-C
+                strike: strike.replace('d', ".").parse().map_err(|_| invalid())?,
+                option_type: match *option_type {
+                    "C" => OptionType::Call,
+                    "P" => OptionType::Put,
+                    _ => throw!(invalid()),
+                },
+            },
+            _ => throw!(invalid()),
+        };
+
+        InstrumentName {
+            currency,
+            quote_currency,
+            kind,
+        }
+    }
+}
+
+struct Expiry(NaiveDate);
+
+impl Display for Expiry {
+    fn fmt(&self, f: &mut Formatter) -> StdResult<(), FmtError> {
+        let month = MONTHS[self.0.month0() as usize];
+        write!(f, "{}{}{:02}", self.0.day(), month, self.0.year() % 100)
+    }
+}
+
+impl Display for InstrumentName {
+    fn fmt(&self, f: &mut Formatter) -> StdResult<(), FmtError> {
+        write!(f, "{}", self.currency)?;
+        if let Some(quote_currency) = self.quote_currency {
+            write!(f, "_{}", quote_currency)?;
+        }
+        match self.kind {
+            InstrumentKind::Perpetual => write!(f, "-PERPETUAL"),
+            InstrumentKind::Future { expiry } => write!(f, "-{}", Expiry(expiry)),
+            InstrumentKind::Option {
+                expiry,
+                strike,
+                option_type,
+            } => {
+                let strike = strike.to_string().replace('.', "d");
+                let option_type = match option_type {
+                    OptionType::Call => "C",
+                    OptionType::Put => "P",
+                };
+                write!(f, "-{}-{}-{}", Expiry(expiry), strike, option_type)
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::{Either, Request};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct JSONRPCRequest<Q: Request> {
@@ -34,6 +35,23 @@ pub struct JSONRPCSuccessResponse<R> {
     pub us_diff: u64,
 }
 
+/// Just enough of an incoming frame to route it: responses carry the id of the request they answer,
+/// notifications carry a method and no id.
+#[derive(Deserialize, Clone, Debug)]
+pub struct JSONRPCFrame {
+    pub id: Option<i64>,
+    pub method: Option<String>,
+}
+
+impl JSONRPCFrame {
+    pub fn response_id(&self) -> Option<i64> {
+        match (self.id, &self.method) {
+            (Some(id), None) => Some(id),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Copy)]
 pub enum JSONRPCVersion {
     #[serde(rename = "2.0")]
@@ -44,6 +62,9 @@ pub enum JSONRPCVersion {
 pub struct ErrorDetail {
     pub code: i64,
     pub message: String,
+    /// Details on the failure, e.g. the faulty parameter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
 }
 
 // {
@@ -2,10 +2,11 @@ use crate::{
     define_request,
     models::{AssetKind, Currency, Request},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-use super::subscription::{Greeks, Stats};
+use super::subscription::{Greeks, Stats, TradesData};
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct GetBookSummaryByCurrencyRequest {
@@ -35,6 +36,13 @@ impl GetBookSummaryByCurrencyRequest {
             kind: Some(AssetKind::Option),
         }
     }
+
+    pub fn spot(currency: Currency) -> Self {
+        Self {
+            currency,
+            kind: Some(AssetKind::Spot),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -61,11 +69,153 @@ pub struct GetBookSummaryByCurrencyResponse {
     pub volume_usd: Option<f64>,
 }
 
+impl GetBookSummaryByCurrencyResponse {
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: Duration) -> bool {
+        crate::util::is_stale(self.creation_timestamp, now, max_age)
+    }
+
+    pub fn strike(&self) -> Option<f64> {
+        crate::util::strike(&self.instrument_name)
+    }
+
+    pub fn expiry(&self) -> Option<NaiveDate> {
+        crate::util::expiry(&self.instrument_name)
+    }
+}
+
 impl Request for GetBookSummaryByCurrencyRequest {
     const METHOD: &'static str = "public/get_book_summary_by_currency";
     type Response = Vec<GetBookSummaryByCurrencyResponse>;
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetBookSummaryByInstrumentRequest {
+    pub instrument_name: String,
+}
+
+impl GetBookSummaryByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
+impl Request for GetBookSummaryByInstrumentRequest {
+    const METHOD: &'static str = "public/get_book_summary_by_instrument";
+    type Response = Vec<GetBookSummaryByCurrencyResponse>;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetContractSizeRequest {
+    pub instrument_name: String,
+}
+
+impl GetContractSizeRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetContractSizeResponse {
+    pub contract_size: f64,
+}
+
+impl Request for GetContractSizeRequest {
+    const METHOD: &'static str = "public/get_contract_size";
+    type Response = GetContractSizeResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct CurrencyAggregate {
+    pub volume: f64,
+    pub volume_usd: f64,
+    pub open_interest: f64,
+    pub mark_price: Option<f64>, // Volume weighted, None without any volume
+}
+
+/// Sums up the book summaries of a currency, fields missing from a summary are skipped.
+pub fn aggregate_book_summary(summaries: &[GetBookSummaryByCurrencyResponse]) -> CurrencyAggregate {
+    let mut aggregate = CurrencyAggregate::default();
+    let mut weighted_mark_price = 0.;
+    for summary in summaries {
+        aggregate.volume += summary.volume;
+        aggregate.volume_usd += summary.volume_usd.unwrap_or(0.);
+        aggregate.open_interest += summary.open_interest.unwrap_or(0.);
+        weighted_mark_price += summary.mark_price * summary.volume;
+    }
+    if aggregate.volume > 0. {
+        aggregate.mark_price = Some(weighted_mark_price / aggregate.volume);
+    }
+    aggregate
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetCurrenciesRequest;
+
+/// `currency` is kept as a string so currencies unknown to `Currency` are still listed.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetCurrenciesResponse {
+    pub coin_type: String,
+    pub currency: String,
+    pub currency_long: String,
+    pub fee_precision: Option<u64>,
+    pub min_confirmations: Option<u64>,
+    pub min_withdrawal_fee: Option<f64>,
+    pub withdrawal_fee: f64,
+}
+
+impl Request for GetCurrenciesRequest {
+    const METHOD: &'static str = "public/get_currencies";
+    const HAS_PAYLOAD: bool = false;
+    type Response = Vec<GetCurrenciesResponse>;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetTradeVolumesRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended: Option<bool>,
+}
+
+impl GetTradeVolumesRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also asks for the volumes over the last 7 and 30 days.
+    pub fn extended() -> Self {
+        Self {
+            extended: Some(true),
+        }
+    }
+}
+
+/// Volumes over the last 24 hours, in the currency. The 7 and 30 days ones come with `extended`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TradeVolume {
+    pub currency: Currency,
+    pub calls_volume: f64,
+    pub puts_volume: f64,
+    pub futures_volume: f64,
+    pub spot_volume: f64,
+    pub calls_volume_7d: Option<f64>,
+    pub puts_volume_7d: Option<f64>,
+    pub futures_volume_7d: Option<f64>,
+    pub spot_volume_7d: Option<f64>,
+    pub calls_volume_30d: Option<f64>,
+    pub puts_volume_30d: Option<f64>,
+    pub futures_volume_30d: Option<f64>,
+    pub spot_volume_30d: Option<f64>,
+}
+
+impl Request for GetTradeVolumesRequest {
+    const METHOD: &'static str = "public/get_trade_volumes";
+    type Response = Vec<TradeVolume>;
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct GetIndexPriceRequest {
     pub index_name: String,
@@ -122,6 +272,10 @@ impl GetInstrumentsRequest {
         Self::with_kind(currency, AssetKind::Option)
     }
 
+    pub fn spot(currency: Currency) -> Self {
+        Self::with_kind(currency, AssetKind::Spot)
+    }
+
     pub fn with_kind(currency: Currency, kind: AssetKind) -> Self {
         Self {
             currency: Some(currency),
@@ -143,6 +297,7 @@ pub enum GetInstrumentsResponse {
         is_active: bool,
         min_trade_amount: f64,
         quote_currency: Currency,
+        settlement_currency: Option<Currency>,
         settlement_period: String,
         tick_size: f64,
     },
@@ -155,6 +310,7 @@ pub enum GetInstrumentsResponse {
         is_active: bool,
         min_trade_amount: f64,
         quote_currency: Currency,
+        settlement_currency: Option<Currency>,
         settlement_period: String,
         tick_size: f64,
     },
@@ -168,6 +324,7 @@ pub enum GetInstrumentsResponse {
         min_trade_amount: f64,
         option_type: String,
         quote_currency: Currency,
+        settlement_currency: Option<Currency>,
         settlement_period: String,
         strike: f64,
         tick_size: f64,
@@ -181,6 +338,7 @@ pub enum GetInstrumentsResponse {
         is_active: bool,
         min_trade_amount: f64,
         quote_currency: Currency,
+        settlement_currency: Option<Currency>,
         settlement_period: String,
         tick_size: f64,
     },
@@ -193,6 +351,7 @@ pub enum GetInstrumentsResponse {
         is_active: bool,
         min_trade_amount: f64,
         quote_currency: Currency,
+        settlement_currency: Option<Currency>,
         tick_size: f64,
     },
 }
@@ -217,6 +376,86 @@ impl GetInstrumentsResponse {
             } => instrument_name,
         }
     }
+
+    pub fn is_active(&self) -> bool {
+        match self {
+            Self::Future { is_active, .. } => *is_active,
+            Self::FutureCombo { is_active, .. } => *is_active,
+            Self::Option { is_active, .. } => *is_active,
+            Self::OptionCombo { is_active, .. } => *is_active,
+            Self::Spot { is_active, .. } => *is_active,
+        }
+    }
+
+    /// Far in the future for perpetuals and spot pairs, which never expire.
+    /// `None` if the timestamp sent by the server is out of range.
+    pub fn expiration(&self) -> Option<DateTime<Utc>> {
+        let expiration_timestamp = match self {
+            Self::Future {
+                expiration_timestamp,
+                ..
+            } => expiration_timestamp,
+            Self::FutureCombo {
+                expiration_timestamp,
+                ..
+            } => expiration_timestamp,
+            Self::Option {
+                expiration_timestamp,
+                ..
+            } => expiration_timestamp,
+            Self::OptionCombo {
+                expiration_timestamp,
+                ..
+            } => expiration_timestamp,
+            Self::Spot {
+                expiration_timestamp,
+                ..
+            } => expiration_timestamp,
+        };
+        DateTime::from_timestamp_millis(*expiration_timestamp as i64)
+    }
+
+    /// The strike of an option, `None` for the other kinds.
+    pub fn strike(&self) -> Option<f64> {
+        match self {
+            Self::Option { strike, .. } => Some(*strike),
+            _ => None,
+        }
+    }
+
+    /// The currency profits and losses are paid in, e.g. USDC for linear instruments.
+    pub fn settlement_currency(&self) -> Option<Currency> {
+        match self {
+            Self::Future {
+                settlement_currency,
+                ..
+            } => *settlement_currency,
+            Self::FutureCombo {
+                settlement_currency,
+                ..
+            } => *settlement_currency,
+            Self::Option {
+                settlement_currency,
+                ..
+            } => *settlement_currency,
+            Self::OptionCombo {
+                settlement_currency,
+                ..
+            } => *settlement_currency,
+            Self::Spot {
+                settlement_currency,
+                ..
+            } => *settlement_currency,
+        }
+    }
+}
+
+/// The instruments still open for trading.
+pub fn active_instruments(instruments: Vec<GetInstrumentsResponse>) -> Vec<GetInstrumentsResponse> {
+    instruments
+        .into_iter()
+        .filter(GetInstrumentsResponse::is_active)
+        .collect()
 }
 
 impl Request for GetInstrumentsRequest {
@@ -224,6 +463,24 @@ impl Request for GetInstrumentsRequest {
     type Response = Vec<GetInstrumentsResponse>;
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetInstrumentRequest {
+    pub instrument_name: String,
+}
+
+impl GetInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
+impl Request for GetInstrumentRequest {
+    const METHOD: &'static str = "public/get_instrument";
+    type Response = GetInstrumentsResponse;
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct GetFundingRateValueRequest {
     pub instrument_name: String,
@@ -248,6 +505,182 @@ impl Request for GetFundingRateValueRequest {
     type Response = GetFundingRateValueResponse;
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetFundingChartDataRequest {
+    pub instrument_name: String,
+    /// The period covered, `8h`, `24h` or `1m`
+    pub length: String,
+}
+
+impl GetFundingChartDataRequest {
+    pub fn new(instrument_name: &str, length: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            length: length.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FundingChartPoint {
+    pub timestamp: u64,
+    pub index_price: f64,
+    pub interest_8h: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetFundingChartDataResponse {
+    pub current_interest: f64,
+    pub interest_8h: f64,
+    pub data: Vec<FundingChartPoint>,
+}
+
+impl Request for GetFundingChartDataRequest {
+    const METHOD: &'static str = "public/get_funding_chart_data";
+    type Response = GetFundingChartDataResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetMarkPriceHistoryRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+}
+
+impl GetMarkPriceHistoryRequest {
+    pub fn new(instrument_name: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+        }
+    }
+}
+
+/// A `(timestamp, mark_price)` point.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct MarkPriceHistoryEntry(pub u64, pub f64);
+
+impl Request for GetMarkPriceHistoryRequest {
+    const METHOD: &'static str = "public/get_mark_price_history";
+    type Response = Vec<MarkPriceHistoryEntry>;
+}
+
+/// One hourly point of a perpetual's funding rate history, `timestamp` being the end of the hour.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FundingRateHistoryEntry {
+    pub timestamp: u64,
+    pub index_price: f64,
+    pub prev_index_price: f64,
+    pub interest_8h: f64,
+    pub interest_1h: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetFundingRateHistoryRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+}
+
+impl GetFundingRateHistoryRequest {
+    pub fn new(instrument_name: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+        }
+    }
+}
+
+impl Request for GetFundingRateHistoryRequest {
+    const METHOD: &'static str = "public/get_funding_rate_history";
+    type Response = Vec<FundingRateHistoryEntry>;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetVolatilityIndexDataRequest {
+    pub currency: Currency,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub resolution: String,
+}
+
+impl GetVolatilityIndexDataRequest {
+    /// `resolution` is in seconds, e.g. "3600", or "1D".
+    pub fn new(
+        currency: Currency,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        resolution: &str,
+    ) -> Self {
+        Self {
+            currency,
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            resolution: resolution.to_string(),
+        }
+    }
+}
+
+/// A DVOL candle, sent as a `[timestamp, open, high, low, close]` array.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct VolatilityIndexCandle {
+    pub timestamp: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// When the range holds more candles than a response, `continuation` is the end timestamp
+/// to request the previous ones with.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetVolatilityIndexDataResponse {
+    pub data: Vec<VolatilityIndexCandle>,
+    pub continuation: Option<u64>,
+}
+
+impl Request for GetVolatilityIndexDataRequest {
+    const METHOD: &'static str = "public/get_volatility_index_data";
+    type Response = GetVolatilityIndexDataResponse;
+}
+
+const FUNDING_INTERVAL_MS: i64 = 3_600_000;
+
+/// The funding P&L of holding `position_size` from `entry` to `exit`, in the position's units.
+/// Hours only partly held accrue pro rata. Longs pay (and shorts receive) positive rates.
+pub fn accrued_funding(
+    history: &[FundingRateHistoryEntry],
+    position_size: f64,
+    entry: DateTime<Utc>,
+    exit: DateTime<Utc>,
+) -> f64 {
+    let (entry, exit) = (entry.timestamp_millis(), exit.timestamp_millis());
+    history
+        .iter()
+        .map(|point| {
+            let end = point.timestamp as i64;
+            let start = end - FUNDING_INTERVAL_MS;
+            let held = (end.min(exit) - start.max(entry)).max(0);
+            -position_size * point.interest_1h * held as f64 / FUNDING_INTERVAL_MS as f64
+        })
+        .sum()
+}
+
+const FUNDING_PERIOD_MS: i64 = 8 * FUNDING_INTERVAL_MS;
+
+/// The first 00:00, 08:00 or 16:00 UTC funding boundary strictly after `now`,
+/// so right at a boundary it is the following one.
+pub fn next_funding_time(now: DateTime<Utc>) -> DateTime<Utc> {
+    let next = (now.timestamp_millis().div_euclid(FUNDING_PERIOD_MS) + 1) * FUNDING_PERIOD_MS;
+    DateTime::from_timestamp_millis(next).expect("funding time out of range")
+}
+
+pub fn seconds_to_funding(now: DateTime<Utc>) -> f64 {
+    (next_funding_time(now) - now).num_milliseconds() as f64 / 1000.
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct GetOrderBookRequest {
     instrument_name: String,
@@ -306,17 +739,162 @@ pub struct GetOrderBookResponse {
     pub underlying_price: Option<f64>,
 }
 
+impl GetOrderBookResponse {
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: Duration) -> bool {
+        crate::util::is_stale(self.timestamp, now, max_age)
+    }
+
+    /// Bids from the best (highest) price down.
+    pub fn sorted_bids(&self) -> Vec<&Bid> {
+        let mut bids: Vec<_> = self.bids.iter().collect();
+        bids.sort_by(|a, b| b.0.total_cmp(&a.0));
+        bids
+    }
+
+    /// Asks from the best (lowest) price up.
+    pub fn sorted_asks(&self) -> Vec<&Ask> {
+        let mut asks: Vec<_> = self.asks.iter().collect();
+        asks.sort_by(|a, b| a.0.total_cmp(&b.0));
+        asks
+    }
+
+    /// Converts the book sizes of an instrument quoted in `quote_currency` into USD notional, so books of
+    /// inverse and linear instruments can be compared. Sizes are already in USD for inverse instruments,
+    /// and in the base currency for linear (stablecoin quoted) and coin quoted (options) ones.
+    pub fn normalized(&self, quote_currency: Currency) -> NormalizedBook {
+        let notional = |price: f64, size: f64| match quote_currency {
+            Currency::USD => size,
+            Currency::USDC | Currency::USDT => size * price,
+            _ => size * self.index_price,
+        };
+        NormalizedBook {
+            instrument_name: self.instrument_name.clone(),
+            bids: self
+                .sorted_bids()
+                .into_iter()
+                .map(|bid| Bid(bid.0, notional(bid.0, bid.1)))
+                .collect(),
+            asks: self
+                .sorted_asks()
+                .into_iter()
+                .map(|ask| Ask(ask.0, notional(ask.0, ask.1)))
+                .collect(),
+        }
+    }
+
+    /// Renders the best `levels` of both sides next to each other, bids on the left and asks on the right,
+    /// each level with its price, size and the cumulative size up to that level.
+    pub fn ladder(&self, levels: usize) -> String {
+        let side = |levels: Vec<(f64, f64)>| {
+            let mut cumulative = 0.;
+            levels
+                .into_iter()
+                .map(|(price, size)| {
+                    cumulative += size;
+                    (price, size, cumulative)
+                })
+                .collect::<Vec<_>>()
+        };
+        let bids = side(
+            self.sorted_bids()
+                .iter()
+                .take(levels)
+                .map(|b| (b.0, b.1))
+                .collect(),
+        );
+        let asks = side(
+            self.sorted_asks()
+                .iter()
+                .take(levels)
+                .map(|a| (a.0, a.1))
+                .collect(),
+        );
+
+        let mut ladder = format!(
+            "{:>12} {:>12} {:>12} | {:<12} {:<12} {:<12}\n",
+            "Bid Cum", "Bid Size", "Bid Price", "Ask Price", "Ask Size", "Ask Cum"
+        );
+        for i in 0..bids.len().max(asks.len()) {
+            let (bid_cumulative, bid_size, bid_price) = match bids.get(i) {
+                Some((price, size, cumulative)) => {
+                    (cumulative.to_string(), size.to_string(), price.to_string())
+                }
+                None => Default::default(),
+            };
+            let (ask_price, ask_size, ask_cumulative) = match asks.get(i) {
+                Some((price, size, cumulative)) => {
+                    (price.to_string(), size.to_string(), cumulative.to_string())
+                }
+                None => Default::default(),
+            };
+            ladder += &format!(
+                "{:>12} {:>12} {:>12} | {:<12} {:<12} {:<12}\n",
+                bid_cumulative, bid_size, bid_price, ask_price, ask_size, ask_cumulative
+            );
+        }
+        ladder
+    }
+}
+
+/// An order book with the sizes in USD notional, best levels first.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct NormalizedBook {
+    pub instrument_name: String,
+    pub bids: Vec<Bid>,
+    pub asks: Vec<Ask>,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Ask(pub f64, pub f64);
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Bid(pub f64, pub f64);
 
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct TickerRequest {
+    pub instrument_name: String,
+}
+
+impl TickerRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TickerResponse {
+    pub best_ask_amount: f64,
+    pub best_ask_price: Option<f64>,
+    pub best_bid_amount: f64,
+    pub best_bid_price: Option<f64>,
+    pub greeks: Option<Greeks>,
+    pub index_price: f64,
+    pub instrument_name: String,
+    pub last_price: Option<f64>,
+    pub mark_price: f64,
+    pub open_interest: f64,
+    pub state: State,
+    pub stats: Stats,
+    pub timestamp: u64,
+}
+
+impl TickerResponse {
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: Duration) -> bool {
+        crate::util::is_stale(self.timestamp, now, max_age)
+    }
+}
+
+impl Request for TickerRequest {
+    const METHOD: &'static str = "public/ticker";
+    type Response = TickerResponse;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
 pub enum State {
-    #[serde(alias = "open")]
     Open,
-    #[serde(alias = "closed")]
     Closed,
 }
 
@@ -337,3 +915,351 @@ impl GetHistoricalVolatilityRequest {
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct GetHistoricalVolatilityResponse(pub u64, pub f64);
+
+define_request! {
+    Name => GetComboDetails;
+    Method => "public/get_combo_details";
+    Request => {
+        pub combo_id: String,
+    };
+    Response => ComboDetails;
+}
+
+impl GetComboDetailsRequest {
+    pub fn new(combo_id: &str) -> Self {
+        Self {
+            combo_id: combo_id.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ComboDetails {
+    pub creation_timestamp: u64,
+    pub id: String,
+    pub instrument_id: u64,
+    pub legs: Vec<ComboLeg>,
+    pub state: ComboState,
+    pub state_timestamp: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComboState {
+    /// Only tradable through a request for quote
+    Rfq,
+    Active,
+    Inactive,
+}
+
+define_request! {
+    Name => GetComboIds;
+    Method => "public/get_combo_ids";
+    Request => {
+        pub currency: Currency,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub state: Option<ComboState>,
+    };
+    Response => Vec<String>;
+}
+
+impl GetComboIdsRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            state: None,
+        }
+    }
+
+    pub fn with_state(currency: Currency, state: ComboState) -> Self {
+        Self {
+            currency,
+            state: Some(state),
+        }
+    }
+}
+
+/// A leg of a combo, `amount` is the signed ratio of the leg in one unit of the combo.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ComboLeg {
+    pub amount: i64,
+    pub instrument_name: String,
+}
+
+/// Public trades share the same shape whether they come from a request or from the trades channel.
+pub type PublicTrade = TradesData;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Sorting {
+    Asc,
+    Desc,
+    Default,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastTradesByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetLastTradesByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_count(instrument_name: &str, count: u64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            count: Some(count),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetLastTradesByInstrumentResponse {
+    pub trades: Vec<PublicTrade>,
+    pub has_more: bool,
+}
+
+impl Request for GetLastTradesByInstrumentRequest {
+    const METHOD: &'static str = "public/get_last_trades_by_instrument";
+    type Response = GetLastTradesByInstrumentResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettlementType {
+    Settlement,
+    Delivery,
+    Bankruptcy,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetLastSettlementsByInstrumentRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<SettlementType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_start_timestamp: Option<u64>,
+}
+
+impl GetLastSettlementsByInstrumentRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Settlement {
+    pub r#type: SettlementType,
+    pub timestamp: u64,
+    pub instrument_name: Option<String>,
+    pub index_price: Option<f64>,
+    pub mark_price: Option<f64>,
+    pub position: Option<f64>,
+    pub profit_loss: Option<f64>,
+    pub session_profit_loss: Option<f64>,
+    // Set on bankruptcies only
+    pub funded: Option<f64>,
+    pub socialized: Option<f64>,
+}
+
+/// The next page is requested with `continuation`, until it comes back empty.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetLastSettlementsResponse {
+    pub settlements: Vec<Settlement>,
+    pub continuation: Option<String>,
+}
+
+impl Request for GetLastSettlementsByInstrumentRequest {
+    const METHOD: &'static str = "public/get_last_settlements_by_instrument";
+    type Response = GetLastSettlementsResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetDeliveryPricesRequest {
+    pub index_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+impl GetDeliveryPricesRequest {
+    pub fn new(index_name: &str) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_count(index_name: &str, count: u64) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            count: Some(count),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DeliveryPrice {
+    pub date: String,
+    pub delivery_price: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetDeliveryPricesResponse {
+    pub data: Vec<DeliveryPrice>,
+    pub records_total: u64,
+}
+
+impl Request for GetDeliveryPricesRequest {
+    const METHOD: &'static str = "public/get_delivery_prices";
+    type Response = GetDeliveryPricesResponse;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Candle {
+    pub timestamp: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetTradingViewChartDataRequest {
+    pub instrument_name: String,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub resolution: String,
+}
+
+impl GetTradingViewChartDataRequest {
+    /// `resolution` is in minutes, e.g. "1" or "60", or "1D".
+    pub fn new(
+        instrument_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        resolution: &str,
+    ) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            start_timestamp: start.timestamp_millis() as u64,
+            end_timestamp: end.timestamp_millis() as u64,
+            resolution: resolution.to_string(),
+        }
+    }
+}
+
+/// Candles as parallel arrays, `status` is `no_data` when the window has none.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetTradingViewChartDataResponse {
+    pub ticks: Vec<u64>,
+    pub open: Vec<f64>,
+    pub high: Vec<f64>,
+    pub low: Vec<f64>,
+    pub close: Vec<f64>,
+    pub volume: Vec<f64>,
+    pub cost: Vec<f64>,
+    pub status: String,
+}
+
+impl GetTradingViewChartDataResponse {
+    pub fn candles(&self) -> Vec<Candle> {
+        self.ticks
+            .iter()
+            .zip(&self.open)
+            .zip(&self.high)
+            .zip(&self.low)
+            .zip(&self.close)
+            .zip(&self.volume)
+            .map(
+                |(((((&timestamp, &open), &high), &low), &close), &volume)| Candle {
+                    timestamp,
+                    open,
+                    high,
+                    low,
+                    close,
+                    volume,
+                },
+            )
+            .collect()
+    }
+}
+
+impl Request for GetTradingViewChartDataRequest {
+    const METHOD: &'static str = "public/get_tradingview_chart_data";
+    type Response = GetTradingViewChartDataResponse;
+}
+
+/// Aggregates trades into candles of `bucket` width, aligned on multiples of `bucket` since the epoch.
+/// Buckets without trades between the first and the last one are filled with a flat candle
+/// at the previous close and zero volume.
+pub fn candles_from_trades(trades: &[PublicTrade], bucket: Duration) -> Vec<Candle> {
+    let bucket = bucket.as_millis() as u64;
+    assert!(bucket > 0, "Candle bucket must be at least one millisecond");
+
+    let mut trades: Vec<_> = trades.iter().collect();
+    trades.sort_by_key(|trade| (trade.timestamp, trade.trade_seq));
+
+    let mut candles: Vec<Candle> = vec![];
+    for trade in trades {
+        let timestamp = trade.timestamp - trade.timestamp % bucket;
+        match candles.last_mut() {
+            Some(candle) if candle.timestamp == timestamp => {
+                candle.high = candle.high.max(trade.price);
+                candle.low = candle.low.min(trade.price);
+                candle.close = trade.price;
+                candle.volume += trade.amount;
+                continue;
+            }
+            Some(candle) => {
+                let close = candle.close;
+                let mut gap = candle.timestamp + bucket;
+                while gap < timestamp {
+                    candles.push(Candle {
+                        timestamp: gap,
+                        open: close,
+                        high: close,
+                        low: close,
+                        close,
+                        volume: 0.,
+                    });
+                    gap += bucket;
+                }
+            }
+            None => {}
+        }
+        candles.push(Candle {
+            timestamp,
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: trade.amount,
+        });
+    }
+    candles
+}
@@ -1,7 +1,9 @@
 pub mod account;
 pub mod authentication;
+pub mod instrument;
 pub mod jsonrpc;
 pub mod market_data;
+pub mod rfq;
 pub mod session_management;
 pub mod subscription;
 pub mod support;
@@ -11,53 +13,85 @@ pub mod wallet;
 use crate::errors::DeribitError;
 use fehler::throws;
 use serde::{Deserialize, Serialize};
-use serde_json::from_str;
+use serde_json::{from_str, to_value, Value};
 use std::{
     fmt::{Display, Error as FmtError, Formatter},
     result::Result as StdResult,
 };
 
 pub use account::{
-    GetAccountSummaryRequest, GetAccountSummaryResponse, GetPositionsRequest, GetPositionsResponse,
-    GetSubaccountsRequest, GetSubaccountsResponse,
+    coin_pnl_to_usd, ChangeSubaccountNameRequest, ChangeSubaccountNameResponse,
+    CreateSubaccountRequest, FeeSchedule, FeeType, GetAccountSummaryRequest,
+    GetAccountSummaryResponse, GetPositionRequest, GetPositionsRequest, GetPositionsResponse,
+    GetSubaccountsRequest, GetSubaccountsResponse, GetTransactionLogRequest,
+    GetTransactionLogResponse, LegExposure, SubaccountLoginState, ToggleSubaccountLoginRequest,
+    ToggleSubaccountLoginResponse, TransactionLogEntry,
 };
-pub use authentication::{AuthRequest, AuthResponse, GrantType};
-pub use jsonrpc::{JSONRPCRequest, JSONRPCResponse, JSONRPCVersion};
+pub use authentication::{AuthRequest, AuthResponse, GrantType, LogoutRequest};
+pub use instrument::{InstrumentKind, InstrumentName};
+pub use jsonrpc::{JSONRPCFrame, JSONRPCRequest, JSONRPCResponse, JSONRPCVersion};
 pub use market_data::{
-    GetBookSummaryByCurrencyRequest, GetBookSummaryByCurrencyResponse, GetFundingRateValueRequest,
-    GetFundingRateValueResponse, GetIndexPriceRequest, GetIndexPriceResponse,
-    GetInstrumentsRequest, GetInstrumentsResponse, GetOrderBookRequest, GetOrderBookResponse,
+    accrued_funding, active_instruments, aggregate_book_summary, candles_from_trades,
+    next_funding_time, seconds_to_funding, Candle, ComboDetails, ComboLeg, ComboState,
+    CurrencyAggregate, DeliveryPrice, FundingChartPoint, FundingRateHistoryEntry,
+    GetBookSummaryByCurrencyRequest, GetBookSummaryByCurrencyResponse,
+    GetBookSummaryByInstrumentRequest, GetComboDetailsRequest, GetComboIdsRequest,
+    GetContractSizeRequest, GetContractSizeResponse, GetCurrenciesRequest, GetCurrenciesResponse,
+    GetDeliveryPricesRequest, GetDeliveryPricesResponse, GetFundingChartDataRequest,
+    GetFundingChartDataResponse, GetFundingRateHistoryRequest, GetFundingRateValueRequest,
+    GetFundingRateValueResponse, GetIndexPriceRequest, GetIndexPriceResponse, GetInstrumentRequest,
+    GetInstrumentsRequest, GetInstrumentsResponse, GetLastSettlementsByInstrumentRequest,
+    GetLastSettlementsResponse, GetLastTradesByInstrumentRequest,
+    GetLastTradesByInstrumentResponse, GetMarkPriceHistoryRequest, GetOrderBookRequest,
+    GetOrderBookResponse, GetTradeVolumesRequest, GetTradingViewChartDataRequest,
+    GetTradingViewChartDataResponse, GetVolatilityIndexDataRequest, GetVolatilityIndexDataResponse,
+    MarkPriceHistoryEntry, NormalizedBook, PublicTrade, Settlement, SettlementType, Sorting,
+    TickerRequest, TickerResponse, TradeVolume, VolatilityIndexCandle,
 };
+pub use rfq::{GetRfqsRequest, Rfq, SendRfqRequest, SendRfqResponse};
 pub use session_management::{
     CancelOnDisconnectScope, DisableCancelOnDisconnectRequest, DisableCancelOnDisconnectResponse,
-    EnableCancelOnDisconnectRequest, GetCancelOnDisconnectRequest, GetCancelOnDisconnectResponse,
-    SetHeartbeatRequest, SetHeartbeatResponse,
+    EnableCancelOnDisconnectRequest, EnableCancelOnDisconnectResponse,
+    GetCancelOnDisconnectRequest, GetCancelOnDisconnectResponse, SetHeartbeatRequest,
+    SetHeartbeatResponse,
 };
 pub use subscription::{
-    HeartbeatType, PrivateSubscribeRequest, PrivateUnsubscribeRequest, PublicSubscribeRequest,
-    PublicUnsubscribeRequest, SubscribeResponse, SubscriptionData, SubscriptionMessage,
-    SubscriptionParams, WithChannel,
+    Channel, HeartbeatType, Interval, PrivateSubscribeRequest, PrivateUnsubscribeRequest,
+    PublicSubscribeRequest, PublicUnsubscribeRequest, SubscribeResponse, SubscriptionData,
+    SubscriptionMessage, SubscriptionParams, UserEvent, WithChannel,
 };
 pub use support::{
     GetTimeRequest, GetTimeResponse, HelloRequest, HelloResponse, TestRequest, TestResponse,
 };
 pub use trading::{
-    BuyRequest, BuyResponse, CancelAllByCurrencyRequest, CancelAllByInstrumentRequest,
+    maker_ratio, reconcile, split_by_liquidity, AnyCurrency, BuyRequest, BuyResponse,
+    CancelAllByCurrencyRequest, CancelAllByInstrumentRequest, CancelAllByKindOrTypeRequest,
     CancelAllRequest, CancelAllResponse, CancelByLabelRequest, CancelOrderType, CancelRequest,
-    CancelResponse, EditRequest, EditResponse, GetOpenOrderType, GetOpenOrdersByCurrencyRequest,
+    CancelResponse, ClosePositionRequest, ClosePositionResponse, ClosePositionType, Currencies,
+    DesiredQuote, EditByLabelRequest, EditRequest, EditResponse, GetMarginsRequest,
+    GetMarginsResponse, GetMmpConfigRequest, GetOpenOrderType, GetOpenOrdersByCurrencyRequest,
     GetOpenOrdersByCurrencyResponse, GetOpenOrdersByInstrumentRequest,
-    GetOpenOrdersByInstrumentResponse, GetOrderStateRequest, GetOrderStateResponse, Order,
-    SellRequest, SellResponse, Trade, TradeRequest, TradeResponse,
+    GetOpenOrdersByInstrumentResponse, GetOpenOrdersByLabelRequest, GetOrderStateRequest,
+    GetOrderStateResponse, GetTriggerOrderHistoryRequest, GetTriggerOrderHistoryResponse,
+    GetUserTradesByCurrencyRequest, GetUserTradesByCurrencyResponse, MmpConfig, Order, OrderId,
+    ReconcilePlan, ResetMmpRequest, ResetMmpResponse, SellRequest, SellResponse,
+    SetMmpConfigRequest, Trade, TradeRequest, TradeResponse, TriggerOrderEntry, UserTrade,
 };
 pub use wallet::{
-    GetTransfersRequest, GetTransfersResponse, SubmitTransferToSubaccountRequest,
-    SubmitTransferToSubaccountResponse, SubmitTransferToUserRequest, SubmitTransferToUserResponse,
-    TransferItem, WithdrawRequest, WithdrawResponse,
+    CancelTransferByIdRequest, CancelTransferByIdResponse, CancelWithdrawalRequest,
+    CancelWithdrawalResponse, Deposit, GetDepositsRequest, GetDepositsResponse,
+    GetTransfersRequest, GetTransfersResponse, GetWithdrawalsRequest, GetWithdrawalsResponse,
+    SubmitTransferToSubaccountRequest, SubmitTransferToSubaccountResponse,
+    SubmitTransferToUserRequest, SubmitTransferToUserResponse, TransferItem, WithdrawRequest,
+    WithdrawResponse, Withdrawal,
 };
 
 pub trait Request {
     const METHOD: &'static str;
     const HAS_PAYLOAD: bool = true;
+    /// Whether sending the request twice is harmless, which lets the client retry it.
+    /// Order placements and fund movements are not.
+    const IDEMPOTENT: bool = true;
     type Response;
 
     fn no_payload(&self) -> bool {
@@ -65,6 +99,18 @@ pub trait Request {
     }
 }
 
+/// Lets the client retry a request that is not idempotent, e.g. an order placement,
+/// for callers that deal with the duplicates a retry may create.
+#[derive(Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct Retriable<R>(pub R);
+
+impl<R: Request> Request for Retriable<R> {
+    const METHOD: &'static str = R::METHOD;
+    const HAS_PAYLOAD: bool = R::HAS_PAYLOAD;
+    type Response = R::Response;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Currency {
     #[serde(alias = "btc")]
@@ -107,6 +153,12 @@ impl std::fmt::Display for Currency {
     }
 }
 
+impl From<Currency> for String {
+    fn from(currency: Currency) -> String {
+        currency.to_string()
+    }
+}
+
 impl std::str::FromStr for Currency {
     type Err = DeribitError;
     #[throws(DeribitError)]
@@ -117,17 +169,12 @@ impl std::str::FromStr for Currency {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum AssetKind {
-    #[serde(alias = "future")]
     Future,
-    #[serde(alias = "future_combo")]
     FutureCombo,
-    #[serde(alias = "option")]
     Option,
-    #[serde(alias = "option_combo")]
     OptionCombo,
-    #[serde(alias = "spot")]
     Spot,
 }
 
@@ -140,6 +187,30 @@ impl std::str::FromStr for AssetKind {
     }
 }
 
+/// The serde name, e.g. `future_combo`, as used in the channel names.
+impl Display for AssetKind {
+    fn fmt(&self, f: &mut Formatter) -> StdResult<(), FmtError> {
+        match to_value(self) {
+            Ok(Value::String(kind)) => write!(f, "{}", kind),
+            _ => Err(FmtError),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Moneyness {
+    ITM,
+    ATM,
+    OTM,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
@@ -189,6 +260,10 @@ pub enum OrderType {
     Market,
     StopLimit,
     StopMarket,
+    TakeLimit,
+    TakeMarket,
+    MarketLimit,
+    TrailingStop,
     Liquidation,
 }
 
@@ -208,6 +283,9 @@ pub enum OrderState {
     Cancelled,
     Untriggered,
     Archive,
+    /// Trigger orders only, before and after they trigger
+    New,
+    Triggered,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -259,6 +337,19 @@ pub enum TransferState {
     WaitingForAdmin,
     #[serde(rename = "rejection_reason")]
     RejectionReason,
+    #[serde(rename = "insufficient_funds")]
+    InsufficientFunds,
+    #[serde(rename = "withdrawal_limit")]
+    WithdrawalLimit,
+}
+
+/// Whether a transfer takes funds from the account or brings them in.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum TransferDirection {
+    #[serde(rename = "payment")]
+    Payment,
+    #[serde(rename = "income")]
+    Income,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -269,7 +360,7 @@ pub enum TransferType {
     Subaccount,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub enum Priority {
     #[serde(rename = "insane")]
     Insane,
@@ -287,12 +378,24 @@ pub enum Priority {
     VeryLow,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum DepositState {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "rejected")]
+    Rejected,
+    #[serde(rename = "replaced")]
+    Replaced,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub enum WithdrawState {
     #[serde(rename = "unconfirmed")]
     Unconfirmed,
     #[serde(rename = "confirmed")]
-    Priority,
+    Confirmed,
     #[serde(rename = "cancelled")]
     Cancelled,
     #[serde(rename = "completed")]
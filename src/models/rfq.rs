@@ -0,0 +1,78 @@
+use crate::models::{AssetKind, Currency, Direction, Request};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetRfqsRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+}
+
+impl GetRfqsRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            kind: None,
+        }
+    }
+
+    pub fn with_kind(currency: Currency, kind: AssetKind) -> Self {
+        Self {
+            currency,
+            kind: Some(kind),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Rfq {
+    pub instrument_name: String,
+    pub amount: Option<f64>,
+    pub side: Option<Direction>,
+    pub last_rfq_tstamp: u64,
+    pub traded_volume: f64,
+}
+
+impl Request for GetRfqsRequest {
+    const METHOD: &'static str = "public/get_rfqs";
+    type Response = Vec<Rfq>;
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SendRfqRequest {
+    pub instrument_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<Direction>,
+}
+
+impl SendRfqRequest {
+    pub fn new(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            amount: None,
+            side: None,
+        }
+    }
+
+    pub fn with_side(instrument_name: &str, side: Direction, amount: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            amount: Some(amount),
+            side: Some(side),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SendRfqResponse {
+    Ok,
+}
+
+impl Request for SendRfqRequest {
+    const METHOD: &'static str = "private/send_rfq";
+    const IDEMPOTENT: bool = false;
+    type Response = SendRfqResponse;
+}
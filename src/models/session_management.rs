@@ -38,7 +38,7 @@ impl Request for DisableHeartbeatRequest {
     type Response = DisableHeartbeatResponse;
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum CancelOnDisconnectScope {
     Connection,
@@ -93,7 +93,7 @@ pub enum DisableCancelOnDisconnectResponse {
 
 impl Request for DisableCancelOnDisconnectRequest {
     const METHOD: &'static str = "private/disable_cancel_on_disconnect";
-    type Response = DisableCancelOnDisconnectRequest;
+    type Response = DisableCancelOnDisconnectResponse;
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
@@ -113,8 +113,8 @@ impl GetCancelOnDisconnectRequest {
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub struct GetCancelOnDisconnectResponse {
-    enabled: bool,
-    scope: CancelOnDisconnectScope,
+    pub enabled: bool,
+    pub scope: CancelOnDisconnectScope,
 }
 
 impl Request for GetCancelOnDisconnectRequest {
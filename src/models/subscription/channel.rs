@@ -0,0 +1,250 @@
+use crate::models::{AssetKind, Currency};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// How often a channel sends its notifications.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interval {
+    /// Every change, only available to authenticated connections.
+    Raw,
+    Ms100,
+    Agg2,
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Interval::Raw => write!(f, "raw"),
+            Interval::Ms100 => write!(f, "100ms"),
+            Interval::Agg2 => write!(f, "agg2"),
+        }
+    }
+}
+
+/// A subscription channel, displayed as the name the server expects,
+/// e.g. `Channel::ticker("BTC-PERPETUAL", Interval::Ms100)` is `ticker.BTC-PERPETUAL.100ms`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Channel {
+    Announcements,
+    Book {
+        instrument_name: String,
+        interval: Interval,
+    },
+    ChartTrades {
+        instrument_name: String,
+        resolution: String,
+    },
+    GroupedBook {
+        instrument_name: String,
+        group: String,
+        depth: u32,
+        interval: Interval,
+    },
+    DeribitPriceIndex(String),
+    DeribitPriceRanking(String),
+    EstimatedExpirationPrice(String),
+    MarkPriceOptions(String),
+    Perpetual {
+        instrument_name: String,
+        interval: Interval,
+    },
+    PlatformState,
+    Quote(String),
+    Ticker {
+        instrument_name: String,
+        interval: Interval,
+    },
+    Trades {
+        instrument_name: String,
+        interval: Interval,
+    },
+    TradesByKind {
+        kind: Option<AssetKind>,
+        currency: Currency,
+        interval: Interval,
+    },
+    UserChanges {
+        kind: Option<AssetKind>,
+        currency: Currency,
+        interval: Interval,
+    },
+    UserOrders {
+        kind: Option<AssetKind>,
+        currency: Currency,
+        interval: Interval,
+    },
+    UserPortfolio(Currency),
+    UserTrades {
+        kind: Option<AssetKind>,
+        currency: Currency,
+        interval: Interval,
+    },
+}
+
+impl Channel {
+    /// The incremental order book, see `BookData`.
+    pub fn book_deltas<I: Into<String>>(instrument_name: I, interval: Interval) -> Channel {
+        Channel::Book {
+            instrument_name: instrument_name.into(),
+            interval,
+        }
+    }
+
+    /// Snapshots of the `depth` best levels, with prices rounded to `group` ("none" to keep them).
+    pub fn book<I, G>(instrument_name: I, group: G, depth: u32, interval: Interval) -> Channel
+    where
+        I: Into<String>,
+        G: Into<String>,
+    {
+        Channel::GroupedBook {
+            instrument_name: instrument_name.into(),
+            group: group.into(),
+            depth,
+            interval,
+        }
+    }
+
+    /// Candles of `resolution` minutes, e.g. "1" or "60", or "1D", updated on every trade.
+    pub fn chart_trades<I, R>(instrument_name: I, resolution: R) -> Channel
+    where
+        I: Into<String>,
+        R: Into<String>,
+    {
+        Channel::ChartTrades {
+            instrument_name: instrument_name.into(),
+            resolution: resolution.into(),
+        }
+    }
+
+    pub fn deribit_price_index<I: Into<String>>(index_name: I) -> Channel {
+        Channel::DeribitPriceIndex(index_name.into())
+    }
+
+    pub fn markprice_options<I: Into<String>>(index_name: I) -> Channel {
+        Channel::MarkPriceOptions(index_name.into())
+    }
+
+    pub fn perpetual<I: Into<String>>(instrument_name: I, interval: Interval) -> Channel {
+        Channel::Perpetual {
+            instrument_name: instrument_name.into(),
+            interval,
+        }
+    }
+
+    pub fn quote<I: Into<String>>(instrument_name: I) -> Channel {
+        Channel::Quote(instrument_name.into())
+    }
+
+    pub fn ticker<I: Into<String>>(instrument_name: I, interval: Interval) -> Channel {
+        Channel::Ticker {
+            instrument_name: instrument_name.into(),
+            interval,
+        }
+    }
+
+    pub fn trades<I: Into<String>>(instrument_name: I, interval: Interval) -> Channel {
+        Channel::Trades {
+            instrument_name: instrument_name.into(),
+            interval,
+        }
+    }
+
+    /// Whether the channel needs an authenticated connection and `private/subscribe`.
+    pub fn is_private(&self) -> bool {
+        matches!(
+            self,
+            Channel::UserChanges { .. }
+                | Channel::UserOrders { .. }
+                | Channel::UserPortfolio(..)
+                | Channel::UserTrades { .. }
+        )
+    }
+}
+
+/// The kind segment of the channels by currency, `any` matching every kind.
+struct Kind(Option<AssetKind>);
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.0 {
+            None => write!(f, "any"),
+            Some(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Channel::Announcements => write!(f, "announcements"),
+            Channel::Book {
+                instrument_name,
+                interval,
+            } => write!(f, "book.{}.{}", instrument_name, interval),
+            Channel::ChartTrades {
+                instrument_name,
+                resolution,
+            } => write!(f, "chart.trades.{}.{}", instrument_name, resolution),
+            Channel::GroupedBook {
+                instrument_name,
+                group,
+                depth,
+                interval,
+            } => write!(
+                f,
+                "book.{}.{}.{}.{}",
+                instrument_name, group, depth, interval
+            ),
+            Channel::DeribitPriceIndex(index_name) => {
+                write!(f, "deribit_price_index.{}", index_name)
+            }
+            Channel::DeribitPriceRanking(index_name) => {
+                write!(f, "deribit_price_ranking.{}", index_name)
+            }
+            Channel::EstimatedExpirationPrice(index_name) => {
+                write!(f, "estimated_expiration_price.{}", index_name)
+            }
+            Channel::MarkPriceOptions(index_name) => write!(f, "markprice.options.{}", index_name),
+            Channel::Perpetual {
+                instrument_name,
+                interval,
+            } => write!(f, "perpetual.{}.{}", instrument_name, interval),
+            Channel::PlatformState => write!(f, "platform_state"),
+            Channel::Quote(instrument_name) => write!(f, "quote.{}", instrument_name),
+            Channel::Ticker {
+                instrument_name,
+                interval,
+            } => write!(f, "ticker.{}.{}", instrument_name, interval),
+            Channel::Trades {
+                instrument_name,
+                interval,
+            } => write!(f, "trades.{}.{}", instrument_name, interval),
+            Channel::TradesByKind {
+                kind,
+                currency,
+                interval,
+            } => write!(f, "trades.{}.{}.{}", Kind(*kind), currency, interval),
+            Channel::UserChanges {
+                kind,
+                currency,
+                interval,
+            } => write!(f, "user.changes.{}.{}.{}", Kind(*kind), currency, interval),
+            Channel::UserOrders {
+                kind,
+                currency,
+                interval,
+            } => write!(f, "user.orders.{}.{}.{}", Kind(*kind), currency, interval),
+            Channel::UserPortfolio(currency) => write!(f, "user.portfolio.{}", currency),
+            Channel::UserTrades {
+                kind,
+                currency,
+                interval,
+            } => write!(f, "user.trades.{}.{}.{}", Kind(*kind), currency, interval),
+        }
+    }
+}
+
+impl From<Channel> for String {
+    fn from(channel: Channel) -> String {
+        channel.to_string()
+    }
+}
@@ -22,7 +22,7 @@ impl<'de> Deserialize<'de> for AnnouncementsChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         if s == "announcements" {
             Ok(AnnouncementsChannel)
         } else {
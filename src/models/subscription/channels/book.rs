@@ -32,7 +32,7 @@ impl<'de> Deserialize<'de> for BookChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["book", instrument_name, interval] => Ok(BookChannel(
@@ -40,7 +40,7 @@ impl<'de> Deserialize<'de> for BookChannel {
                 interval.to_string(),
             )),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"book.{instrument_name}.{interval}"
             )),
         }
@@ -78,7 +78,7 @@ impl<'de> Deserialize<'de> for GroupedBookChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["book", instrument_name, group, depth, interval] => Ok(GroupedBookChannel(
@@ -88,7 +88,7 @@ impl<'de> Deserialize<'de> for GroupedBookChannel {
                 interval.to_string(),
             )),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"book.{instrument_name}.{group}.{depth}.{interval}"
             )),
         }
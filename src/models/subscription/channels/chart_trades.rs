@@ -0,0 +1,62 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The candle in progress, sent again on every trade until the next one starts.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChartTradesData {
+    pub tick: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartTradesChannel {
+    pub instrument_name: String,
+    /// In minutes, e.g. "1" or "60", or "1D"
+    pub resolution: String,
+}
+
+impl<'de> Deserialize<'de> for ChartTradesChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["chart", "trades", instrument_name, resolution] => Ok(ChartTradesChannel {
+                instrument_name: instrument_name.to_string(),
+                resolution: resolution.to_string(),
+            }),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(&s),
+                &"chart.trades.{instrument_name}.{resolution}"
+            )),
+        }
+    }
+}
+impl Serialize for ChartTradesChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl std::fmt::Display for ChartTradesChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chart.trades.{}.{}",
+            self.instrument_name, self.resolution
+        )
+    }
+}
@@ -18,14 +18,14 @@ impl<'de> Deserialize<'de> for DeribitPriceIndexChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["deribit_price_index", index_name] => {
                 Ok(DeribitPriceIndexChannel(index_name.to_string()))
             }
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"deribit_price_index.{index_name}"
             )),
         }
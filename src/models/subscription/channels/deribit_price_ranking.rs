@@ -21,14 +21,14 @@ impl<'de> Deserialize<'de> for DeribitPriceRankingChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["deribit_price_ranking", index_name] => {
                 Ok(DeribitPriceRankingChannel(index_name.to_string()))
             }
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"deribit_price_ranking.{index_name}"
             )),
         }
@@ -18,14 +18,14 @@ impl<'de> Deserialize<'de> for EstimatedExpirationPriceChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["estimated_expiration_price", index_name] => {
                 Ok(EstimatedExpirationPriceChannel(index_name.to_string()))
             }
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"estimated_expiration_price.{index_name}"
             )),
         }
@@ -28,7 +28,7 @@ impl<'de> Deserialize<'de> for InstrumentStateChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["instrument", "state", kind, currency] => Ok(InstrumentStateChannel(
@@ -36,7 +36,7 @@ impl<'de> Deserialize<'de> for InstrumentStateChannel {
                 currency.to_string(),
             )),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"instrument.state.{kind}.{currency}"
             )),
         }
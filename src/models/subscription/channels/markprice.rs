@@ -26,14 +26,14 @@ impl<'de> Deserialize<'de> for MarkPriceOptionChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["markprice", "options", index_name] => {
                 Ok(MarkPriceOptionChannel(index_name.to_string()))
             }
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"markprice.options.{index_name}"
             )),
         }
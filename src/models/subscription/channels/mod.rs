@@ -1,12 +1,15 @@
 mod announcements;
 mod book;
+mod chart_trades;
 mod deribit_price_index;
 mod deribit_price_ranking;
 mod estimated_expiration_price;
 mod instrument;
 mod markprice;
 mod perpetual;
+mod platform_state;
 mod quote;
+mod rfq;
 mod ticker;
 mod trades;
 mod user_changes;
@@ -16,6 +19,7 @@ mod user_trades;
 
 pub use announcements::{AnnouncementsChannel, AnnouncementsData};
 pub use book::{BookChannel, BookData, Delta, GroupedBookChannel, GroupedBookData, OrderBookDelta};
+pub use chart_trades::{ChartTradesChannel, ChartTradesData};
 pub use deribit_price_index::{DeribitPriceIndexChannel, DeribitPriceIndexData};
 pub use deribit_price_ranking::{DeribitPriceRankingChannel, DeribitPriceRankingData};
 pub use estimated_expiration_price::{
@@ -24,7 +28,9 @@ pub use estimated_expiration_price::{
 pub use instrument::{InstrumentState, InstrumentStateChannel, InstrumentStateData};
 pub use markprice::{MarkPriceOptionChannel, MarkPriceOptionData};
 pub use perpetual::{PerpetualChannel, PerpetualData};
+pub use platform_state::{PlatformStateChannel, PlatformStateData};
 pub use quote::{QuoteChannel, QuoteData};
+pub use rfq::{RfqsChannel, RfqsData};
 pub use ticker::{Greeks, Stats, TickerChannel, TickerData};
 pub use trades::{TradesChannel, TradesData};
 pub use user_changes::{UserChangesChannel, UserChangesData};
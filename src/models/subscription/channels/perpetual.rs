@@ -16,7 +16,7 @@ impl<'de> Deserialize<'de> for PerpetualChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["perpetual", instrument_name, interval] => Ok(PerpetualChannel(
@@ -24,7 +24,7 @@ impl<'de> Deserialize<'de> for PerpetualChannel {
                 interval.to_string(),
             )),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"perpetual.{instrument_name}.{interval}"
             )),
         }
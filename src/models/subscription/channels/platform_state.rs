@@ -0,0 +1,48 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// A change of the platform state, each notification carries only the fields that changed,
+/// e.g. `locked` together with the affected `price_index`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct PlatformStateData {
+    pub allow_unauthenticated_public_requests: Option<bool>,
+    pub locked: Option<bool>,
+    pub maintenance: Option<bool>,
+    pub price_index: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlatformStateChannel;
+impl<'de> Deserialize<'de> for PlatformStateChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if s == "platform_state" {
+            Ok(PlatformStateChannel)
+        } else {
+            throw!(D::Error::invalid_value(
+                Unexpected::Str(&s),
+                &"platform_state"
+            ))
+        }
+    }
+}
+impl Serialize for PlatformStateChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("platform_state")
+    }
+}
+
+impl std::fmt::Display for PlatformStateChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "platform_state")
+    }
+}
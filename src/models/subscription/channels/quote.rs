@@ -4,12 +4,13 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
+/// The best bid and ask, a side without orders has no price and a zero amount.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct QuoteData {
     pub best_ask_amount: f64,
-    pub best_ask_price: f64,
+    pub best_ask_price: Option<f64>,
     pub best_bid_amount: f64,
-    pub best_bid_price: f64,
+    pub best_bid_price: Option<f64>,
     pub instrument_name: String,
     pub timestamp: u64,
 }
@@ -21,12 +22,12 @@ impl<'de> Deserialize<'de> for QuoteChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["quote", instrument_name] => Ok(QuoteChannel(instrument_name.to_string())),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"quote.{instrument_name}"
             )),
         }
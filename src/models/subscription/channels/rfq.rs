@@ -0,0 +1,40 @@
+use fehler::throw;
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+pub use crate::models::rfq::Rfq as RfqsData;
+
+#[derive(Debug, Clone)]
+pub struct RfqsChannel(pub String);
+impl<'de> Deserialize<'de> for RfqsChannel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let segments: Vec<_> = s.split(".").collect();
+        match segments.as_slice() {
+            ["rfq", currency] => Ok(RfqsChannel(currency.to_string())),
+            _ => throw!(D::Error::invalid_value(
+                Unexpected::Str(&s),
+                &"rfq.{currency}"
+            )),
+        }
+    }
+}
+impl Serialize for RfqsChannel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl std::fmt::Display for RfqsChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rfq.{}", self.0)
+    }
+}
@@ -63,7 +63,7 @@ impl<'de> Deserialize<'de> for TickerChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["ticker", instrument_name, interval] => Ok(TickerChannel(
@@ -71,7 +71,7 @@ impl<'de> Deserialize<'de> for TickerChannel {
                 interval.to_string(),
             )),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"ticker.{instrument_name}.{interval}"
             )),
         }
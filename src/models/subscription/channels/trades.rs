@@ -41,7 +41,7 @@ impl<'de> Deserialize<'de> for TradesChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["trades", instrument_name, interval] => Ok(TradesChannel::ByInstrument {
@@ -54,7 +54,7 @@ impl<'de> Deserialize<'de> for TradesChannel {
                 interval: interval.to_string(),
             }),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"trades.{instrument_name}.{interval} or trades.{kind}.{currency}.{interval}"
             )),
         }
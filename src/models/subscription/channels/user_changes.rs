@@ -31,7 +31,7 @@ impl<'de> Deserialize<'de> for UserChangesChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["user", "changes", instrument_name, interval] => Ok(UserChangesChannel::ByInstrument {
@@ -44,7 +44,7 @@ impl<'de> Deserialize<'de> for UserChangesChannel {
                 interval: interval.to_string(),
             }),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"user.changes.{instrument_name}.{interval} or user.changes.{kind}.{currency}.{interval}"
             )),
         }
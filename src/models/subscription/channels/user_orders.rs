@@ -55,7 +55,7 @@ impl<'de> Deserialize<'de> for UserOrdersChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["user", "orders", instrument_name, interval] => Ok(UserOrdersChannel::ByInstrument {
@@ -68,7 +68,7 @@ impl<'de> Deserialize<'de> for UserOrdersChannel {
                 interval: interval.to_string(),
             }),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"user.orders.{instrument_name}.{interval} or user.orders.{kind}.{currency}.{interval}"
             )),
         }
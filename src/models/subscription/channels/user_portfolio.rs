@@ -37,19 +37,25 @@ pub struct UserPortfolioData {
     pub total_pl: f64,
 }
 
-#[derive(Debug, Clone)]
-pub struct UserPortfolioChannel(String);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UserPortfolioChannel(pub Currency);
 impl<'de> Deserialize<'de> for UserPortfolioChannel {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
-            ["user", "portfolio", currency] => Ok(UserPortfolioChannel(currency.to_string())),
+            ["user", "portfolio", currency] => match currency.parse() {
+                Ok(currency) => Ok(UserPortfolioChannel(currency)),
+                Err(_) => throw!(D::Error::invalid_value(
+                    Unexpected::Str(currency),
+                    &"a currency"
+                )),
+            },
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"user.portfolio.{currency}"
             )),
         }
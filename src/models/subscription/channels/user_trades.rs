@@ -52,7 +52,7 @@ impl<'de> Deserialize<'de> for UserTradesChannel {
     where
         D: Deserializer<'de>,
     {
-        let s = <&str as Deserialize<'de>>::deserialize(deserializer)?;
+        let s = String::deserialize(deserializer)?;
         let segments: Vec<_> = s.split(".").collect();
         match segments.as_slice() {
             ["user", "trades", instrument_name, interval] => Ok(UserTradesChannel::ByInstrument {
@@ -65,7 +65,7 @@ impl<'de> Deserialize<'de> for UserTradesChannel {
                 interval: interval.to_string(),
             }),
             _ => throw!(D::Error::invalid_value(
-                Unexpected::Str(s),
+                Unexpected::Str(&s),
                 &"user.trades.{instrument_name}.{interval} or trades.{kind}.{currency}.{interval}"
             )),
         }
@@ -1,17 +1,20 @@
+mod channel;
 mod channels;
 
 use crate::models::{jsonrpc::JSONRPCVersion, Request};
+pub use channel::{Channel, Interval};
 pub use channels::{
-    AnnouncementsChannel, AnnouncementsData, BookChannel, BookData, Delta,
-    DeribitPriceIndexChannel, DeribitPriceIndexData, DeribitPriceRankingChannel,
-    DeribitPriceRankingData, EstimatedExpirationPriceChannel, EstimatedExpirationPriceData, Greeks,
-    GroupedBookChannel, GroupedBookData, InstrumentState, InstrumentStateChannel,
-    InstrumentStateData, MarkPriceOptionChannel, MarkPriceOptionData, OrderBookDelta,
-    PerpetualChannel, PerpetualData, QuoteChannel, QuoteData, Stats, TickerChannel, TickerData,
+    AnnouncementsChannel, AnnouncementsData, BookChannel, BookData, ChartTradesChannel,
+    ChartTradesData, Delta, DeribitPriceIndexChannel, DeribitPriceIndexData,
+    DeribitPriceRankingChannel, DeribitPriceRankingData, EstimatedExpirationPriceChannel,
+    EstimatedExpirationPriceData, Greeks, GroupedBookChannel, GroupedBookData, InstrumentState,
+    InstrumentStateChannel, InstrumentStateData, MarkPriceOptionChannel, MarkPriceOptionData,
+    OrderBookDelta, PerpetualChannel, PerpetualData, PlatformStateChannel, PlatformStateData,
+    QuoteChannel, QuoteData, RfqsChannel, RfqsData, Stats, TickerChannel, TickerData,
     TradesChannel, TradesData, UserChangesChannel, UserChangesData, UserOrdersChannel,
     UserOrdersData, UserPortfolioChannel, UserPortfolioData, UserTradesChannel, UserTradesData,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::value::StrDeserializer, Deserialize, Deserializer, Serialize};
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct PublicSubscribeRequest {
@@ -24,6 +27,12 @@ impl PublicSubscribeRequest {
             channels: channels.to_vec(),
         }
     }
+
+    pub fn from_channels(channels: &[Channel]) -> Self {
+        Self {
+            channels: channels.iter().map(Channel::to_string).collect(),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -37,6 +46,12 @@ impl PrivateSubscribeRequest {
             channels: channels.to_vec(),
         }
     }
+
+    pub fn from_channels(channels: &[Channel]) -> Self {
+        Self {
+            channels: channels.iter().map(Channel::to_string).collect(),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -104,6 +119,13 @@ impl SubscriptionMessage {
     pub fn is_subscription(&self) -> bool {
         self.method.is_subscription()
     }
+    /// The channel the notification came from, none for heartbeats.
+    pub fn channel(&self) -> Option<&str> {
+        match &self.params {
+            SubscriptionParams::Subscription(data) => Some(data.channel()),
+            SubscriptionParams::Heartbeat { .. } => None,
+        }
+    }
     pub fn is_heartbeat(&self) -> bool {
         self.method.is_heartbeat()
     }
@@ -160,10 +182,38 @@ pub enum HeartbeatType {
     TestRequest,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 pub struct WithChannel<C, D> {
     pub channel: C,
     pub data: D,
+    /// The channel name exactly as the server sent it.
+    #[serde(skip)]
+    pub raw_channel: String,
+}
+
+impl<'de, C, D> Deserialize<'de> for WithChannel<C, D>
+where
+    C: Deserialize<'de>,
+    D: Deserialize<'de>,
+{
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: Deserializer<'de>,
+    {
+        // Owned, as not every deserializer can lend a borrowed string, e.g. `from_value`
+        #[derive(Deserialize)]
+        struct Raw<D> {
+            channel: String,
+            data: D,
+        }
+
+        let Raw { channel, data } = Raw::deserialize(deserializer)?;
+        Ok(WithChannel {
+            channel: C::deserialize(StrDeserializer::<De::Error>::new(&channel))?,
+            data,
+            raw_channel: channel,
+        })
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -171,6 +221,7 @@ pub struct WithChannel<C, D> {
 pub enum SubscriptionData {
     Announcements(WithChannel<AnnouncementsChannel, AnnouncementsData>),
     Book(WithChannel<BookChannel, BookData>),
+    ChartTrades(WithChannel<ChartTradesChannel, ChartTradesData>),
     DeribitPriceIndex(WithChannel<DeribitPriceIndexChannel, DeribitPriceIndexData>),
     DeribitPriceRanking(WithChannel<DeribitPriceRankingChannel, Vec<DeribitPriceRankingData>>),
     EstimatedExpirationPrice(
@@ -180,7 +231,9 @@ pub enum SubscriptionData {
     InstrumentState(WithChannel<InstrumentStateChannel, InstrumentStateData>),
     MarkPriceOption(WithChannel<MarkPriceOptionChannel, Vec<MarkPriceOptionData>>),
     Perpetual(WithChannel<PerpetualChannel, PerpetualData>),
+    PlatformState(WithChannel<PlatformStateChannel, PlatformStateData>),
     Quote(WithChannel<QuoteChannel, QuoteData>),
+    Rfqs(WithChannel<RfqsChannel, RfqsData>),
     Ticker(WithChannel<TickerChannel, TickerData>),
     Trades(WithChannel<TradesChannel, Vec<TradesData>>), // This should be put after user trades otherwise all usertrades will be deserialized to trades
     UserChanges(WithChannel<UserChangesChannel, UserChangesData>),
@@ -189,3 +242,63 @@ pub enum SubscriptionData {
     UserPortfolio(WithChannel<UserPortfolioChannel, UserPortfolioData>),
     UserTrades(WithChannel<UserTradesChannel, Vec<UserTradesData>>),
 }
+
+impl SubscriptionData {
+    /// The channel name exactly as the server sent it, the parsed one is in `channel`.
+    pub fn channel(&self) -> &str {
+        match self {
+            SubscriptionData::Announcements(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::Book(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::ChartTrades(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::DeribitPriceIndex(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::DeribitPriceRanking(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::EstimatedExpirationPrice(WithChannel { raw_channel, .. }) => {
+                raw_channel
+            }
+            SubscriptionData::GroupedBook(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::InstrumentState(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::MarkPriceOption(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::Perpetual(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::PlatformState(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::Quote(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::Rfqs(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::Ticker(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::Trades(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::UserChanges(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::UserOrders(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::UserOrdersBatch(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::UserPortfolio(WithChannel { raw_channel, .. }) => raw_channel,
+            SubscriptionData::UserTrades(WithChannel { raw_channel, .. }) => raw_channel,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum UserEvent {
+    Order(UserOrdersData),
+    Trade(UserTradesData),
+    Change(UserChangesData),
+    Portfolio(UserPortfolioData),
+}
+
+impl UserEvent {
+    /// Splits the data of a user channel notification into events, other channels yield nothing.
+    pub fn from_data(data: SubscriptionData) -> Vec<UserEvent> {
+        match data {
+            SubscriptionData::UserOrders(WithChannel { data, .. }) => vec![UserEvent::Order(data)],
+            SubscriptionData::UserOrdersBatch(WithChannel { data, .. }) => {
+                data.into_iter().map(UserEvent::Order).collect()
+            }
+            SubscriptionData::UserTrades(WithChannel { data, .. }) => {
+                data.into_iter().map(UserEvent::Trade).collect()
+            }
+            SubscriptionData::UserChanges(WithChannel { data, .. }) => {
+                vec![UserEvent::Change(data)]
+            }
+            SubscriptionData::UserPortfolio(WithChannel { data, .. }) => {
+                vec![UserEvent::Portfolio(data)]
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -1,9 +1,59 @@
 use crate::models::{
     AdvanceOption, AssetKind, Currency, Direction, Either, LiquidityType, OrderState, OrderType,
-    Request, TimeInForce, Trigger,
+    Request, Sorting, TimeInForce, Trigger,
 };
 use serde::{Deserialize, Deserializer, Serialize};
 use shrinkwraprs::Shrinkwrap;
+use std::fmt::{Display, Error as FmtError, Formatter};
+
+/// The id of an order, kept apart from plain strings so it cannot be mixed up
+/// with an instrument name.
+///
+/// ```compile_fail
+/// use deribit::models::{GetOrderBookRequest, OrderId};
+///
+/// let id = OrderId::from("ETH-1416712080");
+/// let _ = GetOrderBookRequest::new(&id);
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct OrderId(pub String);
+
+impl OrderId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for OrderId {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<String> for OrderId {
+    fn from(id: String) -> Self {
+        OrderId(id)
+    }
+}
+
+impl From<&str> for OrderId {
+    fn from(id: &str) -> Self {
+        OrderId(id.to_string())
+    }
+}
+
+impl From<&OrderId> for OrderId {
+    fn from(id: &OrderId) -> Self {
+        id.clone()
+    }
+}
+
+impl From<OrderId> for String {
+    fn from(id: OrderId) -> Self {
+        id.0
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug, Clone, Shrinkwrap)]
 #[shrinkwrap(mutable)]
@@ -26,10 +76,73 @@ impl BuyRequest {
     {
         BuyRequest(TradeRequest::limit(instrument_name, price, amount))
     }
+    pub fn stop_market<I>(
+        instrument_name: I,
+        amount: f64,
+        trigger: Trigger,
+        trigger_price: f64,
+    ) -> BuyRequest
+    where
+        I: Into<String>,
+    {
+        BuyRequest(TradeRequest::stop_market(
+            instrument_name,
+            amount,
+            trigger,
+            trigger_price,
+        ))
+    }
+    pub fn stop_limit<I>(
+        instrument_name: I,
+        amount: f64,
+        price: f64,
+        trigger: Trigger,
+        trigger_price: f64,
+    ) -> BuyRequest
+    where
+        I: Into<String>,
+    {
+        BuyRequest(TradeRequest::stop_limit(
+            instrument_name,
+            amount,
+            price,
+            trigger,
+            trigger_price,
+        ))
+    }
+    pub fn trailing_stop<I>(
+        instrument_name: I,
+        amount: f64,
+        trigger: Trigger,
+        trigger_offset: f64,
+    ) -> BuyRequest
+    where
+        I: Into<String>,
+    {
+        BuyRequest(TradeRequest::trailing_stop(
+            instrument_name,
+            amount,
+            trigger,
+            trigger_offset,
+        ))
+    }
+    pub fn reject_post_only(self, reject: bool) -> BuyRequest {
+        BuyRequest(self.0.reject_post_only(reject))
+    }
+    pub fn post_only(self) -> BuyRequest {
+        BuyRequest(self.0.post_only())
+    }
+    pub fn reduce_only(self) -> BuyRequest {
+        BuyRequest(self.0.reduce_only())
+    }
+    pub fn label<L: Into<String>>(self, label: L) -> BuyRequest {
+        BuyRequest(self.0.label(label))
+    }
 }
 
 impl Request for BuyRequest {
     const METHOD: &'static str = "private/buy";
+    const IDEMPOTENT: bool = false;
     type Response = BuyResponse;
 }
 
@@ -54,16 +167,79 @@ impl SellRequest {
     {
         SellRequest(TradeRequest::limit(instrument_name, price, amount))
     }
+    pub fn stop_market<I>(
+        instrument_name: I,
+        amount: f64,
+        trigger: Trigger,
+        trigger_price: f64,
+    ) -> SellRequest
+    where
+        I: Into<String>,
+    {
+        SellRequest(TradeRequest::stop_market(
+            instrument_name,
+            amount,
+            trigger,
+            trigger_price,
+        ))
+    }
+    pub fn stop_limit<I>(
+        instrument_name: I,
+        amount: f64,
+        price: f64,
+        trigger: Trigger,
+        trigger_price: f64,
+    ) -> SellRequest
+    where
+        I: Into<String>,
+    {
+        SellRequest(TradeRequest::stop_limit(
+            instrument_name,
+            amount,
+            price,
+            trigger,
+            trigger_price,
+        ))
+    }
+    pub fn trailing_stop<I>(
+        instrument_name: I,
+        amount: f64,
+        trigger: Trigger,
+        trigger_offset: f64,
+    ) -> SellRequest
+    where
+        I: Into<String>,
+    {
+        SellRequest(TradeRequest::trailing_stop(
+            instrument_name,
+            amount,
+            trigger,
+            trigger_offset,
+        ))
+    }
+    pub fn reject_post_only(self, reject: bool) -> SellRequest {
+        SellRequest(self.0.reject_post_only(reject))
+    }
+    pub fn post_only(self) -> SellRequest {
+        SellRequest(self.0.post_only())
+    }
+    pub fn reduce_only(self) -> SellRequest {
+        SellRequest(self.0.reduce_only())
+    }
+    pub fn label<L: Into<String>>(self, label: L) -> SellRequest {
+        SellRequest(self.0.label(label))
+    }
 }
 
 impl Request for SellRequest {
     const METHOD: &'static str = "private/sell";
+    const IDEMPOTENT: bool = false;
     type Response = SellResponse;
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct EditRequest {
-    pub order_id: String,
+    pub order_id: OrderId,
     pub amount: f64,
     pub price: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -75,22 +251,25 @@ pub struct EditRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub advanced: Option<AdvanceOption>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop_price: Option<f64>,
+    pub trigger_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mmp: Option<bool>,
 }
 
 impl EditRequest {
-    pub fn new(order_id: &str, price: f64, amount: f64) -> Self {
+    pub fn new<O>(order_id: O, price: f64, amount: f64) -> Self
+    where
+        O: Into<OrderId>,
+    {
         Self {
-            order_id: order_id.to_string(),
+            order_id: order_id.into(),
             amount: amount,
             price: price,
             post_only: None,
             reduce_only: None,
             reject_post_only: None,
             advanced: None,
-            stop_price: None,
+            trigger_price: None,
             mmp: None,
         }
     }
@@ -102,6 +281,37 @@ pub struct EditResponse(pub TradeResponse);
 
 impl Request for EditRequest {
     const METHOD: &'static str = "private/edit";
+    const IDEMPOTENT: bool = false;
+    type Response = EditResponse;
+}
+
+/// Edits the single open order carrying `label` on `instrument_name`.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EditByLabelRequest {
+    pub label: String,
+    pub instrument_name: String,
+    pub amount: f64,
+    pub price: f64,
+}
+
+impl EditByLabelRequest {
+    pub fn new<L, I>(label: L, instrument_name: I, price: f64, amount: f64) -> Self
+    where
+        L: Into<String>,
+        I: Into<String>,
+    {
+        Self {
+            label: label.into(),
+            instrument_name: instrument_name.into(),
+            amount,
+            price,
+        }
+    }
+}
+
+impl Request for EditByLabelRequest {
+    const METHOD: &'static str = "private/edit_by_label";
+    const IDEMPOTENT: bool = false;
     type Response = EditResponse;
 }
 
@@ -117,13 +327,19 @@ pub struct TradeRequest {
     pub time_in_force: TimeInForce,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_show: Option<f64>,
-    pub post_only: bool,
-    pub reduce_only: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop_price: Option<f64>,
+    pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trigger: Option<Trigger>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_offset: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub advanced: Option<AdvanceOption>,
 }
 
@@ -140,10 +356,12 @@ impl TradeRequest {
             price: None,
             time_in_force: TimeInForce::GoodTilCancelled,
             max_show: None,
-            post_only: false,
-            reduce_only: false,
-            stop_price: None,
+            post_only: None,
+            reject_post_only: None,
+            reduce_only: None,
             trigger: None,
+            trigger_price: None,
+            trigger_offset: None,
             advanced: None,
         }
     }
@@ -160,58 +378,96 @@ impl TradeRequest {
             price: Some(price),
             time_in_force: TimeInForce::GoodTilCancelled,
             max_show: None,
-            post_only: false,
-            reduce_only: false,
-            stop_price: None,
+            post_only: None,
+            reject_post_only: None,
+            reduce_only: None,
             trigger: None,
+            trigger_price: None,
+            trigger_offset: None,
             advanced: None,
         }
     }
 
-    pub fn stop_market<I>(instrument_name: I, stop_price: f64, amount: f64) -> TradeRequest
+    /// A market order placed once the `trigger` price crosses `trigger_price`.
+    pub fn stop_market<I>(
+        instrument_name: I,
+        amount: f64,
+        trigger: Trigger,
+        trigger_price: f64,
+    ) -> TradeRequest
     where
         I: Into<String>,
     {
         TradeRequest {
-            instrument_name: instrument_name.into(),
-            amount: amount,
             r#type: OrderType::StopMarket,
-            label: None,
-            price: None,
-            time_in_force: TimeInForce::GoodTilCancelled,
-            max_show: None,
-            post_only: false,
-            reduce_only: false,
-            stop_price: Some(stop_price),
-            trigger: Some(Trigger::LastPrice),
-            advanced: None,
+            trigger: Some(trigger),
+            trigger_price: Some(trigger_price),
+            ..TradeRequest::market(instrument_name, amount)
         }
     }
 
+    /// A limit order at `price` placed once the `trigger` price crosses `trigger_price`.
     pub fn stop_limit<I>(
         instrument_name: I,
-        stop_price: f64,
-        price: f64,
         amount: f64,
+        price: f64,
+        trigger: Trigger,
+        trigger_price: f64,
     ) -> TradeRequest
     where
         I: Into<String>,
     {
         TradeRequest {
-            instrument_name: instrument_name.into(),
-            amount: amount,
             r#type: OrderType::StopLimit,
-            label: None,
-            price: Some(price),
-            time_in_force: TimeInForce::GoodTilCancelled,
-            max_show: None,
-            post_only: false,
-            reduce_only: false,
-            stop_price: Some(stop_price),
-            trigger: Some(Trigger::LastPrice),
-            advanced: None,
+            trigger: Some(trigger),
+            trigger_price: Some(trigger_price),
+            ..TradeRequest::limit(instrument_name, price, amount)
+        }
+    }
+
+    /// A market order placed once the `trigger` price moves `trigger_offset` against its best level.
+    pub fn trailing_stop<I>(
+        instrument_name: I,
+        amount: f64,
+        trigger: Trigger,
+        trigger_offset: f64,
+    ) -> TradeRequest
+    where
+        I: Into<String>,
+    {
+        TradeRequest {
+            r#type: OrderType::TrailingStop,
+            trigger: Some(trigger),
+            trigger_offset: Some(trigger_offset),
+            ..TradeRequest::market(instrument_name, amount)
         }
     }
+
+    /// Makes the order post-only. With `reject` set to false, an order that would
+    /// cross the book is repriced instead of being rejected.
+    pub fn reject_post_only(mut self, reject: bool) -> TradeRequest {
+        self.post_only = Some(true);
+        self.reject_post_only = Some(reject);
+        self
+    }
+
+    /// Makes the order post-only: one that would cross the book is repriced just outside of it.
+    pub fn post_only(mut self) -> TradeRequest {
+        self.post_only = Some(true);
+        self
+    }
+
+    /// Makes the order only reduce the current position.
+    pub fn reduce_only(mut self) -> TradeRequest {
+        self.reduce_only = Some(true);
+        self
+    }
+
+    /// Tags the order, e.g. to cancel or edit it by label later.
+    pub fn label<L: Into<String>>(mut self, label: L) -> TradeRequest {
+        self.label = Some(label.into());
+        self
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -242,6 +498,82 @@ pub struct Trade {
     pub trade_seq: i64,
 }
 
+/// The user's own trades have the same shape whether they come with an order or from the trade history.
+pub type UserTrade = Trade;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetUserTradesByCurrencyRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_old: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<Sorting>,
+}
+
+impl GetUserTradesByCurrencyRequest {
+    pub fn all(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+
+    pub fn futures(currency: Currency) -> Self {
+        Self {
+            currency,
+            kind: Some(AssetKind::Future),
+            ..Default::default()
+        }
+    }
+
+    pub fn options(currency: Currency) -> Self {
+        Self {
+            currency,
+            kind: Some(AssetKind::Option),
+            ..Default::default()
+        }
+    }
+}
+
+/// When `has_more` is set, the next page starts after the last trade id, see `start_id`/`end_id`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetUserTradesByCurrencyResponse {
+    pub trades: Vec<UserTrade>,
+    pub has_more: bool,
+}
+
+impl Request for GetUserTradesByCurrencyRequest {
+    const METHOD: &'static str = "private/get_user_trades_by_currency";
+    type Response = GetUserTradesByCurrencyResponse;
+}
+
+/// Splits fills into the maker ones and the taker ones.
+pub fn split_by_liquidity(trades: &[Trade]) -> (Vec<&Trade>, Vec<&Trade>) {
+    trades
+        .iter()
+        .partition(|trade| trade.liquidity == LiquidityType::Maker)
+}
+
+/// The share of the filled amount that was made, 0 without any fill.
+pub fn maker_ratio(trades: &[Trade]) -> f64 {
+    let (makers, takers) = split_by_liquidity(trades);
+    let made: f64 = makers.iter().map(|trade| trade.amount).sum();
+    let taken: f64 = takers.iter().map(|trade| trade.amount).sum();
+    if made + taken > 0. {
+        made / (made + taken)
+    } else {
+        0.
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Order {
     pub advanced: Option<AdvanceOption>,
@@ -258,7 +590,7 @@ pub struct Order {
     pub label: Option<String>,
     pub last_update_timestamp: u64,
     pub max_show: f64,
-    pub order_id: String,
+    pub order_id: OrderId,
     pub order_state: OrderState,
     pub order_type: OrderType,
     pub post_only: bool,
@@ -269,6 +601,8 @@ pub struct Order {
     pub stop_price: Option<f64>,
     pub time_in_force: TimeInForce,
     pub trigger: Option<Trigger>,
+    pub trigger_offset: Option<f64>,
+    pub trigger_price: Option<f64>,
     pub triggered: Option<bool>,
     pub usd: Option<f64>,
 }
@@ -281,7 +615,173 @@ where
     Ok(p.right())
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+/// A resting limit order a quoting strategy wants to have on the book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesiredQuote {
+    pub instrument_name: String,
+    pub direction: Direction,
+    pub price: f64,
+    pub amount: f64,
+    pub label: Option<String>,
+}
+
+impl DesiredQuote {
+    fn matches(&self, order: &Order) -> bool {
+        order.instrument_name == self.instrument_name
+            && order.direction == self.direction
+            && self
+                .label
+                .as_ref()
+                .is_none_or(|label| order.label.as_ref() == Some(label))
+    }
+
+    fn create(&self) -> Either<BuyRequest, SellRequest> {
+        let mut req = TradeRequest::limit(self.instrument_name.clone(), self.price, self.amount);
+        req.label = self.label.clone();
+        match self.direction {
+            Direction::Sell => Either::Right(SellRequest(req)),
+            _ => Either::Left(BuyRequest(req)),
+        }
+    }
+}
+
+/// The requests turning the open orders into the desired quotes.
+#[derive(Debug, Clone, Default)]
+pub struct ReconcilePlan {
+    pub cancels: Vec<OrderId>,
+    pub edits: Vec<EditRequest>,
+    pub creates: Vec<Either<BuyRequest, SellRequest>>,
+}
+
+impl ReconcilePlan {
+    pub fn is_empty(&self) -> bool {
+        self.cancels.is_empty() && self.edits.is_empty() && self.creates.is_empty()
+    }
+}
+
+/// Pairs each desired quote with an open order on the same instrument and side (and label, when
+/// the quote has one), editing the order only if its price or amount differ. Orders already
+/// matching a quote exactly are paired first, so reordered quotes need no edits. Unpaired orders
+/// are cancelled and unpaired quotes created.
+pub fn reconcile(current: &[Order], desired: &[DesiredQuote]) -> ReconcilePlan {
+    let mut plan = ReconcilePlan::default();
+    let mut paired = vec![false; current.len()];
+    let mut kept = vec![false; desired.len()];
+
+    for (quote, kept) in desired.iter().zip(&mut kept) {
+        let found = current.iter().enumerate().find(|(i, order)| {
+            !paired[*i]
+                && quote.matches(order)
+                && order.price == Some(quote.price)
+                && order.amount == quote.amount
+        });
+        if let Some((i, _)) = found {
+            paired[i] = true;
+            *kept = true;
+        }
+    }
+
+    for (quote, _) in desired.iter().zip(kept).filter(|(_, kept)| !kept) {
+        let found = current
+            .iter()
+            .enumerate()
+            .find(|(i, order)| !paired[*i] && quote.matches(order));
+        match found {
+            Some((i, order)) => {
+                paired[i] = true;
+                plan.edits.push(EditRequest::new(
+                    order.order_id.clone(),
+                    quote.price,
+                    quote.amount,
+                ));
+            }
+            None => plan.creates.push(quote.create()),
+        }
+    }
+
+    plan.cancels = current
+        .iter()
+        .zip(paired)
+        .filter(|(_, paired)| !paired)
+        .map(|(order, _)| order.order_id.clone())
+        .collect();
+    plan
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosePositionType {
+    Limit,
+    Market,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ClosePositionRequest {
+    pub instrument_name: String,
+    pub r#type: ClosePositionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+}
+
+impl ClosePositionRequest {
+    pub fn market(instrument_name: &str) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            r#type: ClosePositionType::Market,
+            price: None,
+        }
+    }
+
+    pub fn limit(instrument_name: &str, price: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            r#type: ClosePositionType::Limit,
+            price: Some(price),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Shrinkwrap)]
+#[shrinkwrap(mutable)]
+pub struct ClosePositionResponse(pub TradeResponse);
+
+impl Request for ClosePositionRequest {
+    const METHOD: &'static str = "private/close_position";
+    const IDEMPOTENT: bool = false;
+    type Response = ClosePositionResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetMarginsRequest {
+    pub instrument_name: String,
+    pub amount: f64,
+    pub price: f64,
+}
+
+impl GetMarginsRequest {
+    pub fn new(instrument_name: &str, amount: f64, price: f64) -> Self {
+        Self {
+            instrument_name: instrument_name.to_string(),
+            amount,
+            price,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetMarginsResponse {
+    pub buy: f64,
+    pub sell: f64,
+    pub max_price: f64,
+    pub min_price: f64,
+}
+
+impl Request for GetMarginsRequest {
+    const METHOD: &'static str = "private/get_margins";
+    type Response = GetMarginsResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum CancelOrderType {
     All,
@@ -297,11 +797,14 @@ impl Default for CancelOrderType {
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct CancelRequest {
-    order_id: String,
+    order_id: OrderId,
 }
 
 impl CancelRequest {
-    pub fn new(order_id: &str) -> Self {
+    pub fn new<O>(order_id: O) -> Self
+    where
+        O: Into<OrderId>,
+    {
         Self {
             order_id: order_id.into(),
         }
@@ -312,7 +815,7 @@ impl CancelRequest {
 pub struct CancelResponse {
     #[serde(flatten)]
     pub order: Order,
-    pub original_order_type: Option<String>,
+    pub original_order_type: Option<OrderType>,
 }
 
 impl Request for CancelRequest {
@@ -353,18 +856,92 @@ impl Request for CancelAllByCurrencyRequest {
     type Response = CancelAllResponse;
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnyCurrency {
+    Any,
+}
+
+/// One currency, several of them or any, sent as `"BTC"`, `["BTC","ETH"]` or `"any"`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Currencies {
+    One(Currency),
+    Many(Vec<Currency>),
+    Any(AnyCurrency),
+}
+
+impl From<Currency> for Currencies {
+    fn from(currency: Currency) -> Currencies {
+        Currencies::One(currency)
+    }
+}
+
+impl From<Vec<Currency>> for Currencies {
+    fn from(currencies: Vec<Currency>) -> Currencies {
+        Currencies::Many(currencies)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CancelAllByKindOrTypeRequest {
+    pub currency: Currencies,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<AssetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<CancelOrderType>,
+}
+
+impl CancelAllByKindOrTypeRequest {
+    pub fn new<C: Into<Currencies>>(currency: C) -> Self {
+        Self {
+            currency: currency.into(),
+            kind: None,
+            r#type: None,
+        }
+    }
+
+    pub fn any() -> Self {
+        Self::new(Currencies::Any(AnyCurrency::Any))
+    }
+
+    pub fn kind(mut self, kind: AssetKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn order_type(mut self, order_type: CancelOrderType) -> Self {
+        self.r#type = Some(order_type);
+        self
+    }
+}
+
+impl Request for CancelAllByKindOrTypeRequest {
+    const METHOD: &'static str = "private/cancel_all_by_kind_or_type";
+    type Response = CancelAllResponse;
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
-pub struct CancelAllResponse(usize);
+pub struct CancelAllResponse(pub usize);
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct CancelByLabelRequest {
     label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<Currency>,
 }
 
 impl CancelByLabelRequest {
     pub fn new<S: Into<String>>(label: S) -> Self {
         Self {
             label: label.into(),
+            currency: None,
+        }
+    }
+    pub fn with_currency<S: Into<String>>(label: S, currency: Currency) -> Self {
+        Self {
+            label: label.into(),
+            currency: Some(currency),
         }
     }
 }
@@ -376,11 +953,14 @@ impl Request for CancelByLabelRequest {
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct GetOrderStateRequest {
-    order_id: String,
+    order_id: OrderId,
 }
 
 impl GetOrderStateRequest {
-    pub fn new(order_id: &str) -> Self {
+    pub fn new<O>(order_id: O) -> Self
+    where
+        O: Into<OrderId>,
+    {
         Self {
             order_id: order_id.into(),
         }
@@ -396,7 +976,68 @@ impl Request for GetOrderStateRequest {
     type Response = GetOrderStateResponse;
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GetTriggerOrderHistoryRequest {
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrument_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+impl GetTriggerOrderHistoryRequest {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_instrument(currency: Currency, instrument_name: &str) -> Self {
+        Self {
+            currency,
+            instrument_name: Some(instrument_name.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// A step in the life of a trigger order: its placement, triggering, cancellation...
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TriggerOrderEntry {
+    /// The order placed once triggered, the trigger order id until then
+    pub order_id: OrderId,
+    pub trigger_order_id: Option<OrderId>,
+    pub order_state: OrderState,
+    pub order_type: Option<OrderType>,
+    pub instrument_name: String,
+    pub trigger: Option<Trigger>,
+    pub trigger_price: Option<f64>,
+    #[serde(alias = "offset")]
+    pub trigger_offset: Option<f64>,
+    pub direction: Direction,
+    pub amount: f64,
+    pub price: Option<f64>,
+    pub label: Option<String>,
+    pub timestamp: u64,
+    pub last_update_timestamp: Option<u64>,
+}
+
+/// The next page is requested with `continuation`, until it comes back empty.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GetTriggerOrderHistoryResponse {
+    pub entries: Vec<TriggerOrderEntry>,
+    pub continuation: Option<String>,
+}
+
+impl Request for GetTriggerOrderHistoryRequest {
+    const METHOD: &'static str = "private/get_trigger_order_history";
+    type Response = GetTriggerOrderHistoryResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum GetOpenOrderType {
     All,
@@ -433,7 +1074,7 @@ impl GetOpenOrdersByCurrencyRequest {
 pub struct GetOpenOrdersByCurrencyResponse {
     #[serde(flatten)]
     pub order: Order,
-    pub original_order_type: Option<String>,
+    pub original_order_type: Option<OrderType>,
 }
 
 impl Request for GetOpenOrdersByCurrencyRequest {
@@ -464,10 +1105,127 @@ impl GetOpenOrdersByInstrumentRequest {
 pub struct GetOpenOrdersByInstrumentResponse {
     #[serde(flatten)]
     pub order: Order,
-    pub original_order_type: Option<String>,
+    pub original_order_type: Option<OrderType>,
 }
 
 impl Request for GetOpenOrdersByInstrumentRequest {
     const METHOD: &'static str = "private/get_open_orders_by_instrument";
     type Response = Vec<GetOpenOrdersByInstrumentResponse>;
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetOpenOrdersByLabelRequest {
+    pub currency: Currency,
+    pub label: String,
+}
+
+impl GetOpenOrdersByLabelRequest {
+    pub fn new(currency: Currency, label: &str) -> Self {
+        Self {
+            currency,
+            label: label.to_string(),
+        }
+    }
+}
+
+impl Request for GetOpenOrdersByLabelRequest {
+    const METHOD: &'static str = "private/get_open_orders_by_label";
+    type Response = Vec<Order>;
+}
+
+/// Market maker protection: once the traded quantity or delta of `index_name` exceeds a limit
+/// within `interval` seconds, the quotes are cancelled and new ones refused for `frozen_time` seconds.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct MmpConfig {
+    pub index_name: String,
+    pub interval: u64,
+    pub frozen_time: u64,
+    pub quantity_limit: Option<f64>,
+    pub delta_limit: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct SetMmpConfigRequest {
+    pub index_name: String,
+    pub interval: u64,
+    pub frozen_time: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity_limit: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_limit: Option<f64>,
+}
+
+impl SetMmpConfigRequest {
+    /// An `interval` of 0 disables the protection.
+    pub fn new(index_name: &str, interval: u64, frozen_time: u64) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+            interval,
+            frozen_time,
+            ..Default::default()
+        }
+    }
+
+    pub fn quantity_limit(mut self, quantity_limit: f64) -> Self {
+        self.quantity_limit = Some(quantity_limit);
+        self
+    }
+
+    pub fn delta_limit(mut self, delta_limit: f64) -> Self {
+        self.delta_limit = Some(delta_limit);
+        self
+    }
+}
+
+impl Request for SetMmpConfigRequest {
+    const METHOD: &'static str = "private/set_mmp_config";
+    type Response = MmpConfig;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct GetMmpConfigRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_name: Option<String>,
+}
+
+impl GetMmpConfigRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_index_name(index_name: &str) -> Self {
+        Self {
+            index_name: Some(index_name.to_string()),
+        }
+    }
+}
+
+impl Request for GetMmpConfigRequest {
+    const METHOD: &'static str = "private/get_mmp_config";
+    type Response = Vec<MmpConfig>;
+}
+
+/// Lifts the freeze triggered by the protection of `index_name`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ResetMmpRequest {
+    pub index_name: String,
+}
+
+impl ResetMmpRequest {
+    pub fn new(index_name: &str) -> Self {
+        Self {
+            index_name: index_name.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetMmpResponse {
+    Ok,
+}
+
+impl Request for ResetMmpRequest {
+    const METHOD: &'static str = "private/reset_mmp";
+    type Response = ResetMmpResponse;
+}
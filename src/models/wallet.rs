@@ -1,5 +1,6 @@
 use crate::models::{
-    Currency, Direction, Priority, Request, TransferState, TransferType, WithdrawState,
+    Currency, DepositState, Priority, Request, TransferDirection, TransferState, TransferType,
+    WithdrawState,
 };
 use serde::{Deserialize, Serialize};
 
@@ -24,21 +25,22 @@ impl GetTransfersRequest {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GetTransfersResponse {
-    count: u64,
-    data: Vec<TransferItem>,
+    pub count: u64,
+    pub data: Vec<TransferItem>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct TransferItem {
-    amount: f64,
-    created_timestamp: u64,
-    currency: Currency,
-    direction: Direction,
-    id: i64,
-    other_side: String,
-    state: TransferState,
-    r#type: TransferType,
-    updated_timestamp: i64,
+    pub amount: f64,
+    pub created_timestamp: u64,
+    pub currency: Currency,
+    pub direction: TransferDirection,
+    pub id: i64,
+    /// The subaccount name or the address the funds are moved to or from
+    pub other_side: String,
+    pub state: TransferState,
+    pub r#type: TransferType,
+    pub updated_timestamp: u64,
 }
 
 impl Request for GetTransfersRequest {
@@ -53,10 +55,22 @@ pub struct SubmitTransferToSubaccountRequest {
     destination: u64,
 }
 
+impl SubmitTransferToSubaccountRequest {
+    /// Moves `amount` to the subaccount with id `destination`.
+    pub fn new(currency: Currency, amount: f64, destination: u64) -> Self {
+        Self {
+            currency,
+            amount,
+            destination,
+        }
+    }
+}
+
 pub type SubmitTransferToSubaccountResponse = TransferItem;
 
 impl Request for SubmitTransferToSubaccountRequest {
     const METHOD: &'static str = "private/submit_transfer_to_subaccount";
+    const IDEMPOTENT: bool = false;
     type Response = SubmitTransferToSubaccountResponse;
 }
 
@@ -64,45 +78,235 @@ impl Request for SubmitTransferToSubaccountRequest {
 pub struct SubmitTransferToUserRequest {
     currency: Currency,
     amount: f64,
-    destination: u64,
+    destination: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     tfa: Option<String>,
 }
 
+impl SubmitTransferToUserRequest {
+    /// Moves `amount` to the account owning the address `destination`, of the address book.
+    pub fn new(currency: Currency, amount: f64, destination: &str) -> Self {
+        Self {
+            currency,
+            amount,
+            destination: destination.to_string(),
+            tfa: None,
+        }
+    }
+
+    pub fn tfa(mut self, tfa: &str) -> Self {
+        self.tfa = Some(tfa.to_string());
+        self
+    }
+}
+
 pub type SubmitTransferToUserResponse = TransferItem;
 
 impl Request for SubmitTransferToUserRequest {
     const METHOD: &'static str = "private/submit_transfer_to_user";
+    const IDEMPOTENT: bool = false;
     type Response = SubmitTransferToUserResponse;
 }
 
+/// Moves funds out of the account, to an address of the withdrawal address book.
+/// This sends real funds on the mainnet: try it on the testnet first.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct WithdrawRequest {
     currency: Currency,
     address: String,
     amount: f64,
-    priority: Priority,
-    destination: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<Priority>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tfa: Option<String>,
 }
 
+impl WithdrawRequest {
+    pub fn new(currency: Currency, address: &str, amount: f64) -> Self {
+        Self {
+            currency,
+            address: address.to_string(),
+            amount,
+            priority: None,
+            tfa: None,
+        }
+    }
+
+    /// Only for BTC withdrawals, `high` by default.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn tfa(mut self, tfa: &str) -> Self {
+        self.tfa = Some(tfa.to_string());
+        self
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct WithdrawResponse {
-    address: String,
-    amount: f64,
-    confirmed_timestamp: u64,
-    created_timestamp: u64,
-    currency: Currency,
-    fee: f64,
-    id: u64,
-    priority: u64,
-    state: WithdrawState,
-    transaction_id: String,
-    updated_timestamp: u64,
+pub struct Withdrawal {
+    pub address: String,
+    pub amount: f64,
+    pub confirmed_timestamp: Option<u64>,
+    pub created_timestamp: u64,
+    pub currency: Currency,
+    pub fee: f64,
+    pub id: u64,
+    pub priority: Option<f64>,
+    pub state: WithdrawState,
+    /// Set once the withdrawal is broadcast
+    pub transaction_id: Option<String>,
+    pub updated_timestamp: u64,
 }
 
+pub type WithdrawResponse = Withdrawal;
+
 impl Request for WithdrawRequest {
-    const METHOD: &'static str = "/private/withdraw";
+    const METHOD: &'static str = "private/withdraw";
+    const IDEMPOTENT: bool = false;
     type Response = WithdrawResponse;
 }
+
+/// Cancels a withdrawal that is not confirmed yet.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CancelWithdrawalRequest {
+    currency: Currency,
+    id: u64,
+}
+
+impl CancelWithdrawalRequest {
+    pub fn new(currency: Currency, id: u64) -> Self {
+        Self { currency, id }
+    }
+}
+
+pub type CancelWithdrawalResponse = Withdrawal;
+
+impl Request for CancelWithdrawalRequest {
+    const METHOD: &'static str = "private/cancel_withdrawal";
+    type Response = CancelWithdrawalResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CancelTransferByIdRequest {
+    currency: Currency,
+    id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tfa: Option<String>,
+}
+
+impl CancelTransferByIdRequest {
+    pub fn new(currency: Currency, id: i64) -> Self {
+        Self {
+            currency,
+            id,
+            tfa: None,
+        }
+    }
+
+    pub fn tfa(mut self, tfa: &str) -> Self {
+        self.tfa = Some(tfa.to_string());
+        self
+    }
+}
+
+pub type CancelTransferByIdResponse = TransferItem;
+
+impl Request for CancelTransferByIdRequest {
+    const METHOD: &'static str = "private/cancel_transfer_by_id";
+    type Response = CancelTransferByIdResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetDepositsRequest {
+    currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+}
+
+impl GetDepositsRequest {
+    pub fn with_currency(currency: Currency) -> Self {
+        Self {
+            currency,
+            count: None,
+            offset: None,
+        }
+    }
+
+    pub fn count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Deposit {
+    pub address: String,
+    pub amount: f64,
+    pub currency: Currency,
+    pub received_timestamp: u64,
+    pub state: DepositState,
+    pub transaction_id: Option<String>,
+    pub updated_timestamp: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetDepositsResponse {
+    /// Total number of deposits, not only those of the page
+    pub count: u64,
+    pub data: Vec<Deposit>,
+}
+
+impl Request for GetDepositsRequest {
+    const METHOD: &'static str = "private/get_deposits";
+    type Response = GetDepositsResponse;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetWithdrawalsRequest {
+    currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+}
+
+impl GetWithdrawalsRequest {
+    pub fn with_currency(currency: Currency) -> Self {
+        Self {
+            currency,
+            count: None,
+            offset: None,
+        }
+    }
+
+    pub fn count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetWithdrawalsResponse {
+    /// Total number of withdrawals, not only those of the page
+    pub count: u64,
+    pub data: Vec<Withdrawal>,
+}
+
+impl Request for GetWithdrawalsRequest {
+    const METHOD: &'static str = "private/get_withdrawals";
+    type Response = GetWithdrawalsResponse;
+}
@@ -0,0 +1,63 @@
+use crate::models::{
+    subscription::UserChangesData, GetPositionsResponse, SubscriptionData, SubscriptionMessage,
+    SubscriptionParams, WithChannel,
+};
+use std::collections::BTreeMap;
+
+/// A running view of the positions, kept up to date from the user.changes notifications.
+#[derive(Debug, Clone, Default)]
+pub struct PositionTracker {
+    positions: BTreeMap<String, GetPositionsResponse>,
+}
+
+impl PositionTracker {
+    pub fn new() -> PositionTracker {
+        PositionTracker::default()
+    }
+
+    /// Starts from the positions returned by `GetPositionsRequest`.
+    pub fn with_positions(positions: Vec<GetPositionsResponse>) -> PositionTracker {
+        let mut tracker = PositionTracker::new();
+        for position in positions {
+            tracker.update(position);
+        }
+        tracker
+    }
+
+    pub fn apply(&mut self, changes: &UserChangesData) {
+        for position in &changes.positions {
+            self.update(position.clone());
+        }
+    }
+
+    /// Applies the message if it is a user.changes notification, returns whether it was one.
+    pub fn apply_message(&mut self, message: &SubscriptionMessage) -> bool {
+        match &message.params {
+            SubscriptionParams::Subscription(SubscriptionData::UserChanges(WithChannel {
+                data,
+                ..
+            })) => {
+                self.apply(data);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn position(&self, instrument_name: &str) -> Option<&GetPositionsResponse> {
+        self.positions.get(instrument_name)
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, GetPositionsResponse> {
+        self.positions.clone()
+    }
+
+    fn update(&mut self, position: GetPositionsResponse) {
+        if position.size() == 0. {
+            self.positions.remove(position.instrument_name());
+        } else {
+            self.positions
+                .insert(position.instrument_name().to_string(), position);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+/// Token bucket refilled at `rate` tokens per second, holding at most `burst` of them.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rate: f64, burst: u32) -> RateLimiter {
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            rate,
+            burst,
+            tokens: burst,
+            last: Instant::now(),
+        }
+    }
+
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub(crate) fn burst(&self) -> u32 {
+        self.burst as u32
+    }
+
+    /// Waits until a token is available and takes it.
+    pub(crate) async fn acquire(&mut self) {
+        loop {
+            let now = Instant::now();
+            let elapsed = now.duration_since(self.last).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+            self.last = now;
+
+            if self.tokens >= 1. {
+                self.tokens -= 1.;
+                return;
+            }
+            sleep(Duration::from_secs_f64((1. - self.tokens) / self.rate)).await;
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    auth_refresh::AuthRefresh,
+    connect::connect_tcp,
+    connection_state::{ConnectionState, SharedState},
+    errors::Result,
+    ConnectStrategy, WSStream,
+};
+use futures::SinkExt;
+use log::warn;
+use serde::Deserialize;
+use serde_json::{from_str, json, Value};
+use std::{collections::BTreeSet, time::Duration};
+use tokio::time::sleep;
+use tokio_tungstenite::client_async_tls;
+use tungstenite::Message;
+use url::Url;
+
+pub(crate) async fn open(url: &Url, strategy: ConnectStrategy) -> Result<WSStream> {
+    let stream = connect_tcp(url, strategy).await?;
+    let (ws, _) = client_async_tls(url.as_str(), stream).await?;
+    Ok(ws)
+}
+
+/// What has to be sent again on a new connection for the caller not to notice the switch:
+/// the heartbeat and the active subscriptions, as seen in the outgoing requests.
+#[derive(Debug, Default)]
+pub(crate) struct Replay {
+    heartbeat: Option<String>,
+    public: BTreeSet<String>,
+    private: BTreeSet<String>,
+    // The server closes the connection after a logout, which must not be reopened
+    logged_out: bool,
+    // Replayed requests get negative ids so they never collide with the API client's
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct Outgoing {
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+impl Replay {
+    pub(crate) fn track(&mut self, payload: &str) {
+        let req = match from_str::<Outgoing>(payload) {
+            Ok(req) => req,
+            Err(_) => return,
+        };
+        let channels: Vec<String> = req.params["channels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|channel| channel.as_str().map(String::from))
+            .collect();
+
+        match req.method.as_str() {
+            "public/auth" => self.logged_out = false,
+            "private/logout" => {
+                self.private.clear();
+                self.logged_out = true;
+            }
+            "public/set_heartbeat" => self.heartbeat = Some(payload.to_string()),
+            "public/disable_heartbeat" => self.heartbeat = None,
+            "public/subscribe" => self.public.extend(channels),
+            "private/subscribe" => self.private.extend(channels),
+            "public/unsubscribe" => {
+                for channel in channels {
+                    self.public.remove(&channel);
+                }
+            }
+            "private/unsubscribe" => {
+                for channel in channels {
+                    self.private.remove(&channel);
+                }
+            }
+            "public/unsubscribe_all" | "private/unsubscribe_all" => {
+                self.public.clear();
+                self.private.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the session was ended with a logout since the last authentication.
+    pub(crate) fn logged_out(&self) -> bool {
+        self.logged_out
+    }
+
+    pub(crate) fn next_id(&mut self) -> i64 {
+        self.id -= 1;
+        self.id
+    }
+
+    /// The requests restoring the session, authentication first. The session is authenticated
+    /// again with the latest refresh token, as the original request may be signed for a moment
+    /// which has passed.
+    pub(crate) fn requests(&mut self, auth: &mut AuthRefresh) -> Vec<String> {
+        let mut requests: Vec<String> = auth.request(self.next_id()).into_iter().collect();
+        if let Some(heartbeat) = &self.heartbeat {
+            if let Ok(mut request) = from_str::<Value>(heartbeat) {
+                request["id"] = self.next_id().into();
+                requests.push(request.to_string());
+            }
+        }
+        for (method, channels) in [
+            ("public/subscribe", self.public.clone()),
+            ("private/subscribe", self.private.clone()),
+        ] {
+            if !channels.is_empty() {
+                let id = self.next_id();
+                requests.push(
+                    json!({"id": id, "method": method, "params": {"channels": channels}})
+                        .to_string(),
+                );
+            }
+        }
+        requests
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Reconnect {
+    pub(crate) url: Url,
+    pub(crate) connect_strategy: ConnectStrategy,
+    pub(crate) max_attempts: usize,
+    pub(crate) state: SharedState,
+}
+
+impl Reconnect {
+    /// Opens a new connection and replays the session on it with `requests`, backing off
+    /// exponentially between attempts.
+    pub(crate) async fn reconnect(&self, requests: &[String]) -> Result<WSStream> {
+        self.state.set(ConnectionState::Reconnecting);
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match open(&self.url, self.connect_strategy).await {
+                Ok(mut ws) => {
+                    for request in requests {
+                        ws.send(Message::Text(request.clone())).await?;
+                    }
+                    self.state.set(ConnectionState::Connected);
+                    return Ok(ws);
+                }
+                Err(e) if attempt < self.max_attempts => {
+                    warn!("[Servo] Reconnection attempt {} failed: {}", attempt, e);
+                    sleep(Duration::from_millis(100 << attempt.min(6))).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    errors::Result,
+    models::{SubscriptionData, SubscriptionMessage, SubscriptionParams},
+};
+use futures::{
+    stream::{select_all, BoxStream, SelectAll},
+    task::{Context, Poll},
+    Stream, StreamExt,
+};
+use serde_json::{to_value, Value};
+use std::{
+    collections::{HashSet, VecDeque},
+    pin::Pin,
+};
+
+/// How many recent updates are remembered to recognize the copies delivered by the other sources.
+const DEDUP_WINDOW: usize = 4096;
+
+/// The channel, instrument and id identifying an update.
+type Key = (String, String, u64);
+
+/// Merges the subscription streams of several connections subscribed to the same channels, e.g. to
+/// different gateways, so each update comes out once. Each message is paired with the index of the
+/// source that delivered it first.
+pub struct StreamMux {
+    sources: SelectAll<BoxStream<'static, (usize, Result<SubscriptionMessage>)>>,
+    seen: HashSet<Key>,
+    order: VecDeque<Key>,
+}
+
+impl StreamMux {
+    pub fn new<S>(sources: Vec<S>) -> StreamMux
+    where
+        S: Stream<Item = Result<SubscriptionMessage>> + Send + 'static,
+    {
+        let sources = sources
+            .into_iter()
+            .enumerate()
+            .map(|(i, source)| source.map(move |message| (i, message)).boxed());
+        StreamMux {
+            sources: select_all(sources),
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Whether the update was already delivered, remembering it otherwise.
+    fn is_duplicate(&mut self, key: Key) -> bool {
+        if self.seen.contains(&key) {
+            return true;
+        }
+        if self.order.len() == DEDUP_WINDOW {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(key.clone());
+        self.order.push_back(key);
+        false
+    }
+}
+
+/// Identifies an update by its channel, its instrument and its change id, trade sequence or
+/// timestamp, whichever the data carries first.
+fn dedup_key(channel: &str, data: &Value) -> Option<Key> {
+    let name = ["instrument_name", "identifier"]
+        .iter()
+        .find_map(|field| data[field].as_str())
+        .unwrap_or_default();
+    let id = ["change_id", "trade_seq", "timestamp"]
+        .iter()
+        .find_map(|field| data[field].as_u64())?;
+    Some((channel.to_string(), name.to_string(), id))
+}
+
+/// Keeps the batch elements flagged in `keep`.
+fn retain<T>(batch: &mut Vec<T>, keep: &[bool]) {
+    let mut keep = keep.iter();
+    batch.retain(|_| *keep.next().unwrap_or(&true));
+}
+
+impl StreamMux {
+    /// Drops the parts of the update that were already delivered, returning whether anything is
+    /// left. Batches are filtered element by element, as the sources may split them differently.
+    fn unseen(&mut self, data: &mut SubscriptionData) -> bool {
+        let value = match to_value(&*data) {
+            Ok(value) => value,
+            Err(_) => return true,
+        };
+        let channel = match value["channel"].as_str() {
+            Some(channel) => channel,
+            None => return true,
+        };
+        let batch = match &value["data"] {
+            Value::Array(batch) => batch,
+            data => {
+                return match dedup_key(channel, data) {
+                    Some(key) => !self.is_duplicate(key),
+                    None => true,
+                }
+            }
+        };
+        let keep: Vec<bool> = batch
+            .iter()
+            .map(|element| match dedup_key(channel, element) {
+                Some(key) => !self.is_duplicate(key),
+                None => true,
+            })
+            .collect();
+        if keep.iter().all(|keep| *keep) {
+            return true;
+        }
+        match data {
+            SubscriptionData::DeribitPriceRanking(ranking) => retain(&mut ranking.data, &keep),
+            SubscriptionData::MarkPriceOption(marks) => retain(&mut marks.data, &keep),
+            SubscriptionData::Trades(trades) => retain(&mut trades.data, &keep),
+            SubscriptionData::UserOrdersBatch(orders) => retain(&mut orders.data, &keep),
+            SubscriptionData::UserTrades(trades) => retain(&mut trades.data, &keep),
+            _ => {}
+        }
+        keep.contains(&true)
+    }
+}
+
+impl Stream for StreamMux {
+    type Item = Result<(usize, SubscriptionMessage)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.sources.poll_next_unpin(cx) {
+                Poll::Ready(Some((source, Ok(mut message)))) => {
+                    let unseen = match &mut message.params {
+                        SubscriptionParams::Subscription(data) => self.unseen(data),
+                        _ => true,
+                    };
+                    if unseen {
+                        return Poll::Ready(Some(Ok((source, message))));
+                    }
+                }
+                Poll::Ready(Some((_, Err(e)))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{errors::Result, models::SubscriptionMessage};
+use crate::{
+    errors::Result,
+    models::{SubscriptionMessage, SubscriptionParams, UserEvent},
+};
 use futures::{
     channel::mpsc,
     task::{Context, Poll},
@@ -8,14 +11,14 @@ use log::warn;
 use pin_project::pin_project;
 use serde::de::DeserializeOwned;
 use serde_json::from_str;
-use std::{marker::PhantomData, pin::Pin};
+use std::{collections::VecDeque, marker::PhantomData, pin::Pin};
 
 pub struct DeribitSubscriptionClient {
-    rx: mpsc::Receiver<String>,
+    rx: mpsc::Receiver<Result<String>>,
 }
 
 impl DeribitSubscriptionClient {
-    pub(crate) fn new(rx: mpsc::Receiver<String>) -> DeribitSubscriptionClient {
+    pub(crate) fn new(rx: mpsc::Receiver<Result<String>>) -> DeribitSubscriptionClient {
         DeribitSubscriptionClient { rx }
     }
 
@@ -33,7 +36,7 @@ impl Stream for DeribitSubscriptionClient {
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         let pin = Pin::new(&mut self.rx);
         match pin.poll_next(cx) {
-            Poll::Ready(Some(v)) => {
+            Poll::Ready(Some(Ok(v))) => {
                 let data = from_str::<SubscriptionMessage>(&v).map_err(From::from);
                 if let Err(_) = data.as_ref() {
                     warn!(
@@ -43,6 +46,8 @@ impl Stream for DeribitSubscriptionClient {
                 }
                 Poll::Ready(Some(data))
             }
+            // The reason the connection ended
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
             Poll::Ready(None) => Poll::Ready(None),
             Poll::Pending => Poll::Pending,
         }
@@ -52,7 +57,7 @@ impl Stream for DeribitSubscriptionClient {
 #[pin_project]
 pub struct DeribitSubscriptionLimitedClient<D> {
     #[pin]
-    rx: mpsc::Receiver<String>,
+    rx: mpsc::Receiver<Result<String>>,
     _ty: PhantomData<D>,
 }
 
@@ -62,7 +67,7 @@ impl<D: DeserializeOwned> Stream for DeribitSubscriptionLimitedClient<D> {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         let this = self.project();
         match this.rx.poll_next(cx) {
-            Poll::Ready(Some(v)) => {
+            Poll::Ready(Some(Ok(v))) => {
                 let data = from_str::<SubscriptionMessage<D>>(&v).map_err(From::from);
                 if let Err(_) = data.as_ref() {
                     warn!(
@@ -72,8 +77,50 @@ impl<D: DeserializeOwned> Stream for DeribitSubscriptionLimitedClient<D> {
                 }
                 Poll::Ready(Some(data))
             }
+            // The reason the connection ended
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
             Poll::Ready(None) => Poll::Ready(None),
             Poll::Pending => Poll::Pending,
         }
     }
 }
+
+pub struct UserStream {
+    inner: DeribitSubscriptionClient,
+    pending: VecDeque<UserEvent>,
+}
+
+impl UserStream {
+    pub(crate) fn new(inner: DeribitSubscriptionClient) -> UserStream {
+        UserStream {
+            inner,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl Stream for UserStream {
+    type Item = Result<UserEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(SubscriptionMessage {
+                    params: SubscriptionParams::Subscription(data),
+                    ..
+                }))) => {
+                    let events = UserEvent::from_data(data);
+                    self.pending.extend(events);
+                }
+                Poll::Ready(Some(Ok(_))) => {} // Heartbeats
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::models::{InstrumentKind, InstrumentName, OptionType};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::time::Duration;
+
+/// The expiry, strike and type of an option instrument, e.g. `BTC-28JUN19-7500-P`.
+fn option_parts(instrument_name: &str) -> Option<(NaiveDate, f64, OptionType)> {
+    match instrument_name.parse::<InstrumentName>().ok()?.kind {
+        InstrumentKind::Option {
+            expiry,
+            strike,
+            option_type,
+        } => Some((expiry, strike, option_type)),
+        _ => None,
+    }
+}
+
+/// Whether an option instrument is a call or a put, `None` if the instrument is not an option.
+pub fn option_type(instrument_name: &str) -> Option<OptionType> {
+    option_parts(instrument_name).map(|(_, _, option_type)| option_type)
+}
+
+/// The strike of an option instrument, `None` if the instrument is not an option.
+pub fn strike(instrument_name: &str) -> Option<f64> {
+    option_parts(instrument_name).map(|(_, strike, _)| strike)
+}
+
+/// The expiry date of an option instrument, `None` if the instrument is not an option.
+pub fn expiry(instrument_name: &str) -> Option<NaiveDate> {
+    option_parts(instrument_name).map(|(expiry, _, _)| expiry)
+}
+
+/// Whether data stamped `timestamp_millis` is older than `max_age` at `now`.
+/// Timestamps ahead of `now` are never stale.
+pub fn is_stale(timestamp_millis: u64, now: DateTime<Utc>, max_age: Duration) -> bool {
+    let age = now.timestamp_millis() - timestamp_millis as i64;
+    age > 0 && age as u128 > max_age.as_millis()
+}
@@ -1,7 +1,12 @@
 use anyhow::Error;
+use chrono::{Duration, Utc};
 use deribit::{
     models::{
-        AuthRequest, Currency, GetAccountSummaryRequest, GetPositionsRequest, GetSubaccountsRequest,
+        coin_pnl_to_usd, AuthRequest, BuyRequest, ChangeSubaccountNameRequest, ComboLeg,
+        CreateSubaccountRequest, Currency, Direction, GetAccountSummaryRequest, GetPositionRequest,
+        GetPositionsRequest, GetPositionsResponse, GetSubaccountsRequest, GetSubaccountsResponse,
+        GetTransactionLogRequest, LegExposure, Moneyness, SellRequest,
+        ToggleSubaccountLoginRequest,
     },
     Deribit, DeribitBuilder,
 };
@@ -74,6 +79,38 @@ fn get_positions() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn get_position() {
+    let AccountTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = AccountTest::default();
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        client
+            .call(BuyRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        let position = client
+            .call(GetPositionRequest::new("BTC-PERPETUAL"))
+            .await?
+            .await?;
+        client
+            .call(SellRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        Ok::<_, Error>(position)
+    };
+    let position = rt.block_on(fut)?;
+    assert_eq!(position.instrument_name(), "BTC-PERPETUAL");
+}
+
 #[test]
 #[throws(Error)]
 fn get_subaccounts() {
@@ -97,3 +134,250 @@ fn get_subaccounts() {
         throw!(err);
     }
 }
+
+#[test]
+#[throws(Error)]
+fn create_and_rename_subaccount() {
+    let AccountTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = AccountTest::default();
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let subaccount = client.call(CreateSubaccountRequest::new()).await?.await?;
+        let name = format!("renamed_{}", subaccount.id);
+        let req = ChangeSubaccountNameRequest::new(subaccount.id, &name);
+        client.call(req).await?.await?;
+        let req = ToggleSubaccountLoginRequest::disable(subaccount.id);
+        client.call(req).await?.await?;
+
+        let subaccounts = client.call(GetSubaccountsRequest::new()).await?.await?;
+        Ok::<_, Error>((subaccount, name, subaccounts))
+    };
+    let (subaccount, name, subaccounts) = rt.block_on(fut)?;
+
+    assert_eq!(subaccount.r#type, "subaccount");
+    let renamed = subaccounts
+        .iter()
+        .find(|account| account.id == subaccount.id)
+        .unwrap();
+    assert_eq!(renamed.username, name);
+    assert!(!renamed.login_enabled);
+}
+
+#[test]
+fn currency_string_forms() {
+    assert_eq!(String::from(Currency::BTC), "BTC");
+    assert_eq!(String::from(Currency::USDC), Currency::USDC.to_string());
+    assert_eq!("btc".parse::<Currency>().unwrap(), Currency::BTC);
+    assert_eq!("ETH".parse::<Currency>().unwrap(), Currency::ETH);
+    assert!("doge".parse::<Currency>().is_err());
+
+    assert_eq!(
+        GetPositionsRequest::futures(Currency::ETH).currency,
+        GetPositionsRequest::futures("ETH").currency
+    );
+}
+
+fn option_position(
+    instrument_name: &str,
+    average_price_usd: f64,
+    index_price: f64,
+) -> GetPositionsResponse {
+    GetPositionsResponse::Option {
+        average_price: 0.01,
+        average_price_usd,
+        delta: 0.,
+        direction: Direction::Buy,
+        floating_profit_loss: 0.,
+        floating_profit_loss_usd: 0.,
+        gamma: 0.,
+        index_price,
+        initial_margin: 0.,
+        instrument_name: instrument_name.to_string(),
+        maintenance_margin: 0.,
+        mark_price: 0.,
+        realized_profit_loss: 0.,
+        settlement_price: 0.,
+        size: 1.,
+        theta: 0.,
+        total_profit_loss: 0.,
+        vega: 0.,
+    }
+}
+
+#[test]
+fn itm_call() {
+    let position = option_position("BTC-28JUN19-7500-C", 300., 8000.);
+    assert_eq!(position.breakeven(), Some(7800.));
+    assert_eq!(position.moneyness(), Some(Moneyness::ITM));
+}
+
+#[test]
+fn otm_put() {
+    let position = option_position("BTC-28JUN19-7500-P", 200., 8000.);
+    assert_eq!(position.breakeven(), Some(7300.));
+    assert_eq!(position.moneyness(), Some(Moneyness::OTM));
+
+    let position = option_position("BTC-28JUN19-7500-P", 200., 7510.);
+    assert_eq!(position.moneyness(), Some(Moneyness::ATM));
+}
+
+#[test]
+#[throws(Error)]
+fn combo_leg_exposures() {
+    let position: GetPositionsResponse = serde_json::from_str(
+        r#"{
+            "kind": "option_combo",
+            "average_price": 0.01,
+            "delta": 0.0,
+            "direction": "sell",
+            "floating_profit_loss": 0.0,
+            "index_price": 40000.0,
+            "initial_margin": 0.0,
+            "instrument_name": "BTC-STRD-29APR22-40000",
+            "maintenance_margin": 0.0,
+            "mark_price": 0.02,
+            "realized_profit_loss": 0.0,
+            "size": -2.0,
+            "total_profit_loss": 0.0
+        }"#,
+    )?;
+    let legs = vec![
+        ComboLeg {
+            amount: 1,
+            instrument_name: "BTC-29APR22-40000-C".into(),
+        },
+        ComboLeg {
+            amount: 1,
+            instrument_name: "BTC-29APR22-40000-P".into(),
+        },
+    ];
+
+    assert_eq!(
+        position.leg_exposures(&legs),
+        Some(vec![
+            LegExposure {
+                instrument_name: "BTC-29APR22-40000-C".into(),
+                signed_amount: -2.,
+            },
+            LegExposure {
+                instrument_name: "BTC-29APR22-40000-P".into(),
+                signed_amount: -2.,
+            },
+        ])
+    );
+    assert_eq!(
+        option_position("BTC-28JUN19-7500-P", 200., 8000.).leg_exposures(&legs),
+        None
+    );
+}
+
+#[test]
+#[throws(Error)]
+fn fee_tier() {
+    let AccountTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = AccountTest::default();
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+        let req = GetAccountSummaryRequest::extended(Currency::BTC);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let summary = rt.block_on(fut)?;
+    assert!(!summary.fee_tier().is_empty());
+    for fee in summary.fee_tier() {
+        assert!(fee.maker_fee <= fee.taker_fee);
+    }
+}
+
+/// A subaccount holding one unit of each of `currencies`, in the order given.
+fn subaccount(currencies: &[&str]) -> GetSubaccountsResponse {
+    let portfolio: Vec<_> = currencies
+        .iter()
+        .map(|currency| {
+            format!(
+                r#""{}":{{"available_funds":1.0,"available_withdrawal_funds":1.0,"balance":1.0,"currency":"{}","equity":1.0,"initial_margin":0.0,"maintenance_margin":0.0,"margin_balance":1.0}}"#,
+                currency.to_lowercase(),
+                currency.to_uppercase()
+            )
+        })
+        .collect();
+    serde_json::from_str(&format!(
+        r#"{{"email":"user_AAA@email.com","id":2,"is_password":true,"login_enabled":true,"portfolio":{{{}}},"receive_notifications":false,"system_name":"user_1","tfa_enabled":false,"type":"subaccount","username":"user_1","referrals_count":0,"security_keys_enabled":false}}"#,
+        portfolio.join(",")
+    ))
+    .unwrap()
+}
+
+#[test]
+fn subaccount_with_usdc_portfolio() {
+    let subaccount = subaccount(&["BTC", "USDC"]);
+    assert_eq!(
+        subaccount.portfolio[&Currency::USDC].currency,
+        Currency::USDC
+    );
+    assert_eq!(subaccount.portfolio[&Currency::BTC].currency, Currency::BTC);
+}
+
+#[test]
+fn portfolio_order() {
+    let subaccount = subaccount(&["USDC", "ETH", "BTC"]);
+    let currencies: Vec<_> = subaccount.portfolio.keys().copied().collect();
+    assert_eq!(currencies, [Currency::BTC, Currency::ETH, Currency::USDC]);
+}
+
+#[test]
+#[throws(Error)]
+fn future_pl_usd() {
+    let position: GetPositionsResponse = serde_json::from_str(
+        r#"{"average_price":7440.18,"delta":0.0051,"direction":"buy","estimated_liquidation_price":null,"floating_profit_loss":0.00002,"index_price":8000.0,"initial_margin":0.00004,"instrument_name":"BTC-PERPETUAL","kind":"future","leverage":34,"maintenance_margin":0.00002,"mark_price":7821.65,"open_orders_margin":0.0,"realized_profit_loss":-0.00001,"settlement_price":7760.65,"size":40.0,"size_currency":0.0051,"total_profit_loss":0.0005}"#,
+    )?;
+    assert_eq!(coin_pnl_to_usd(0.5, 8000.), 4000.);
+    assert!((position.total_pl_usd() - 4.).abs() < 1e-9);
+    assert!((position.floating_pl_usd() - 0.16).abs() < 1e-9);
+
+    let option = option_position("BTC-28JUN19-7500-C", 100., 8000.);
+    assert_eq!(option.floating_pl_usd(), 0.);
+
+    // Linear instruments already make their profits and losses in USDC
+    let linear: GetPositionsResponse = serde_json::from_str(
+        r#"{"average_price":7440.18,"delta":0.0051,"direction":"buy","estimated_liquidation_price":null,"floating_profit_loss":1.5,"index_price":8000.0,"initial_margin":0.4,"instrument_name":"BTC_USDC-PERPETUAL","kind":"future","leverage":34,"maintenance_margin":0.2,"mark_price":7821.65,"open_orders_margin":0.0,"realized_profit_loss":-0.1,"settlement_price":7760.65,"size":0.0051,"size_currency":0.0051,"total_profit_loss":2.5}"#,
+    )?;
+    assert_eq!(linear.total_pl_usd(), 2.5);
+    assert_eq!(linear.floating_pl_usd(), 1.5);
+}
+
+#[test]
+#[throws(Error)]
+fn get_transaction_log() {
+    let AccountTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = AccountTest::default();
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let now = Utc::now();
+        let req = GetTransactionLogRequest::new(Currency::BTC, now - Duration::hours(24), now);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let log = rt.block_on(fut)?;
+    for entry in log.logs {
+        assert_eq!(entry.currency, Currency::BTC);
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::Error;
+use chrono::{TimeZone, Utc};
+use deribit::{
+    models::{AuthRequest, Currency, GetPositionsRequest, GrantType},
+    Deribit, DeribitBuilder, DeribitError,
+};
+use fehler::throws;
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use std::{
+    env::var,
+    iter,
+    time::{Duration, Instant},
+};
+use tokio::{
+    net::TcpListener,
+    runtime::Runtime,
+    time::{sleep, timeout},
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+#[test]
+#[throws(Error)]
+fn auth_introspection() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        assert!(!client.is_authenticated());
+        assert!(client.auth_expiry().is_none());
+
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        assert!(client.is_authenticated());
+        assert!(client.auth_expiry().unwrap() > Utc::now());
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn signature_auth() {
+    // The example of Deribit's documentation
+    let timestamp = Utc.timestamp_millis_opt(1576074319000).unwrap();
+    let req = AuthRequest::signature_auth_at("AMANDA", "AMANDASECRECT", timestamp, "1iqt2wls", "");
+    assert_eq!(req.grant_type, GrantType::ClientSignature);
+    assert_eq!(
+        req.signature.as_deref(),
+        Some("56590594f97921b09b18f166befe0d1319b198bbcdad7ca73382de2f88fe9aa1")
+    );
+
+    let payload: serde_json::Value = serde_json::from_str(&serde_json::to_string(&req)?)?;
+    assert_eq!(payload["grant_type"], "client_signature");
+    assert_eq!(payload["client_id"], "AMANDA");
+    assert_eq!(payload["timestamp"], 1576074319000u64);
+    assert_eq!(payload["nonce"], "1iqt2wls");
+    assert!(payload.get("data").is_none());
+    assert!(payload.get("client_secret").is_none());
+
+    let req =
+        AuthRequest::signature_auth_at("AMANDA", "AMANDASECRECT", timestamp, "abcd", "some data");
+    assert_eq!(
+        req.signature.as_deref(),
+        Some("7d14998014fb87e50bce7f709e4306f854c3b927ff22061d42fa6a03ab92a02b")
+    );
+    assert_eq!(req.data.as_deref(), Some("some data"));
+}
+
+#[test]
+#[throws(Error)]
+fn signature_auth_testnet() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::signature_auth(&key, &secret, "deribit-rs", "");
+        let _ = client.call(req).await?.await?;
+        assert!(client.is_authenticated());
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn refresh_before_expiry() -> Result<(), Error> {
+    // A mock server granting tokens valid for a second, reporting the refresh tokens it gets back
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let (refresh_tx, mut refresh_rx) = mpsc::unbounded();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut granted = 0;
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                if req["params"]["grant_type"] == "refresh_token" {
+                    let token = req["params"]["refresh_token"].as_str().unwrap().to_string();
+                    refresh_tx.unbounded_send((Instant::now(), token)).unwrap();
+                }
+                granted += 1;
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"result":{{"access_token":"a{}","expires_in":1,"refresh_token":"r{}","scope":"connection","token_type":"bearer"}},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"], granted, granted
+                );
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let _ = client
+        .call(AuthRequest::credential_auth("id", "secret"))
+        .await?
+        .await?;
+    let authenticated = Instant::now();
+    let first_expiry = client.auth_expiry().unwrap();
+
+    let (refreshed, token) = timeout(Duration::from_secs(5), refresh_rx.next())
+        .await?
+        .unwrap();
+    assert_eq!(token, "r1");
+    assert!(refreshed - authenticated < Duration::from_secs(1));
+
+    // Each refresh schedules the next one with the new refresh token
+    let (_, token) = timeout(Duration::from_secs(5), refresh_rx.next())
+        .await?
+        .unwrap();
+    assert_eq!(token, "r2");
+    assert!(client.is_authenticated());
+    assert!(client.auth_expiry().unwrap() > first_expiry);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn logout() -> Result<(), Error> {
+    // A mock server granting tokens valid for a second, and rejecting private calls after a logout
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let (request_tx, mut request_rx) = mpsc::unbounded();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut logged_out = false;
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let result = match req["method"].as_str().unwrap() {
+                    "public/auth" => {
+                        r#""result":{"access_token":"a","expires_in":1,"refresh_token":"r","scope":"connection","token_type":"bearer"}"#
+                    }
+                    "private/logout" => {
+                        logged_out = true;
+                        r#""result":null"#
+                    }
+                    _ if logged_out => r#""error":{"code":13009,"message":"unauthorized"}"#,
+                    _ => r#""result":[]"#,
+                };
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},{},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"], result
+                );
+                request_tx.unbounded_send(req).unwrap();
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let _ = client
+        .call(AuthRequest::credential_auth("id", "secret"))
+        .await?
+        .await?;
+    assert!(client.is_authenticated());
+
+    client.logout(true).await?;
+    assert!(!client.is_authenticated());
+
+    let result = client
+        .call(GetPositionsRequest::futures(Currency::BTC))
+        .await?
+        .await;
+    match result {
+        Err(DeribitError::ApiError { code, .. }) => assert_eq!(code, 13009),
+        other => panic!("{:?}", other),
+    }
+
+    // No refresh of the dropped token once it would have been due
+    sleep(Duration::from_millis(1200)).await;
+    let methods: Vec<_> = iter::from_fn(|| request_rx.try_recv().ok())
+        .map(|req| (req["method"].clone(), req["params"].clone()))
+        .collect();
+    assert_eq!(methods.len(), 3, "{:?}", methods);
+    assert_eq!(methods[1].0, "private/logout");
+    assert_eq!(methods[1].1["invalidate_token"], true);
+    Ok(())
+}
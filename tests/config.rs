@@ -0,0 +1,93 @@
+use anyhow::Error;
+use deribit::{DeribitBuilder, DeribitConfig, WS_URL, WS_URL_TESTNET};
+use fehler::throws;
+use serde_json::from_str;
+use std::{
+    env::{set_var, temp_dir},
+    fs::{remove_file, write},
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[test]
+#[throws(Error)]
+fn from_config() {
+    let config: DeribitConfig = from_str(
+        r#"{
+            "testnet": true,
+            "timeout_ms": 1500,
+            "credentials": {
+                "key": "my-key",
+                "secret": {"env": "DERIBIT_CONFIG_TEST_SECRET"}
+            }
+        }"#,
+    )?;
+    let drb = DeribitBuilder::from_config(&config).build()?;
+    assert_eq!(drb.ws_url(), WS_URL_TESTNET);
+    assert_eq!(drb.timeout(), Some(Duration::from_millis(1500)));
+    assert_eq!(drb.rate_limit(), None);
+    assert_eq!(drb.reconnect_attempts(), None);
+
+    assert!(config.auth_request().is_err());
+    set_var("DERIBIT_CONFIG_TEST_SECRET", "my-secret");
+    let req = config.auth_request()?.unwrap();
+    assert_eq!(req.client_id.as_deref(), Some("my-key"));
+    assert_eq!(req.client_secret.as_deref(), Some("my-secret"));
+}
+
+#[test]
+#[throws(Error)]
+fn default_config() {
+    let config: DeribitConfig = from_str("{}")?;
+    let drb = DeribitBuilder::from_config(&config).build()?;
+    assert_eq!(drb.ws_url(), WS_URL);
+    assert_eq!(drb.timeout(), None);
+    assert!(config.auth_request()?.is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn connection_settings_and_credentials_path() {
+    // Unique to this run, so concurrent runs do not read each other's file
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    let path = temp_dir().join(format!(
+        "deribit_config_test_credentials_{}_{}.json",
+        process::id(),
+        nanos
+    ));
+    write(&path, r#"{"key": "file-key", "secret": "file-secret"}"#)?;
+    let config: DeribitConfig = from_str(&format!(
+        r#"{{
+            "rate_limit": {{"requests_per_second": 20.0, "burst": 50}},
+            "reconnect": true,
+            "max_reconnect_attempts": 3,
+            "credentials_path": {}
+        }}"#,
+        serde_json::to_string(&path)?
+    ))?;
+    let drb = DeribitBuilder::from_config(&config).build()?;
+    assert_eq!(drb.rate_limit(), Some((20., 50)));
+    assert_eq!(drb.reconnect_attempts(), Some(3));
+
+    let req = config.auth_request();
+    remove_file(&path)?;
+    let req = req?.unwrap();
+    assert_eq!(req.client_id.as_deref(), Some("file-key"));
+    assert_eq!(req.client_secret.as_deref(), Some("file-secret"));
+}
+
+#[test]
+#[throws(Error)]
+fn invalid_rate_limit() {
+    let config: DeribitConfig =
+        from_str(r#"{"rate_limit": {"requests_per_second": 0.0, "burst": 5}}"#)?;
+    assert!(DeribitBuilder::from_config(&config).build().is_err());
+    assert!(DeribitBuilder::default()
+        .rate_limit(-1., 5)
+        .build()
+        .is_err());
+    assert!(DeribitBuilder::default()
+        .rate_limit(f64::NAN, 5)
+        .build()
+        .is_err());
+}
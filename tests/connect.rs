@@ -0,0 +1,75 @@
+use anyhow::Error;
+use deribit::ConnectStrategy;
+use fehler::throws;
+use futures::future::pending;
+use std::{
+    io,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use tokio::runtime::Runtime;
+
+// What a dual-stack host resolves to, the IPv6 address being unreachable
+fn resolved() -> Vec<SocketAddr> {
+    vec![
+        "[2001:db8::1]:443".parse().unwrap(),
+        "192.0.2.1:443".parse().unwrap(),
+    ]
+}
+
+async fn connect(addr: SocketAddr) -> io::Result<SocketAddr> {
+    if addr.is_ipv6() {
+        pending().await
+    } else {
+        Ok(addr)
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn happy_eyeballs_falls_back() {
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fallback_delay = Duration::from_millis(50);
+    let strategy = ConnectStrategy::HappyEyeballs { fallback_delay };
+
+    let start = Instant::now();
+    let addr = rt.block_on(strategy.connect(&resolved(), connect))?;
+    let elapsed = start.elapsed();
+
+    assert!(addr.is_ipv4());
+    assert!(elapsed >= fallback_delay);
+    assert!(elapsed < fallback_delay * 10);
+}
+
+#[test]
+#[throws(Error)]
+fn happy_eyeballs_skips_delay_on_failure() {
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let strategy = ConnectStrategy::HappyEyeballs {
+        fallback_delay: Duration::from_secs(3600),
+    };
+    let refused = |addr: SocketAddr| async move {
+        if addr.is_ipv6() {
+            Err(io::Error::from(io::ErrorKind::ConnectionRefused))
+        } else {
+            Ok(addr)
+        }
+    };
+
+    let addr = rt.block_on(strategy.connect(&resolved(), refused))?;
+    assert!(addr.is_ipv4());
+}
+
+#[test]
+fn sequential_stalls_on_hanging_address() {
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let addrs = resolved();
+    let attempt = async {
+        let connect = ConnectStrategy::Sequential.connect(&addrs, connect);
+        tokio::time::timeout(Duration::from_millis(100), connect).await
+    };
+    assert!(rt.block_on(attempt).is_err());
+}
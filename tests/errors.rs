@@ -0,0 +1,95 @@
+use anyhow::Error;
+use deribit::{
+    models::{GetTimeRequest, GetTimeResponse, JSONRPCResponse},
+    Deribit, DeribitError,
+};
+use futures::{SinkExt, StreamExt};
+use serde_json::from_str;
+use std::time::Duration;
+use tokio::{net::TcpListener, time::timeout};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deserialization_failure() -> Result<(), Error> {
+    // A mock server answering with a malformed result
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"result":"not a time","usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"]
+                );
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+    let result = client.call(GetTimeRequest).await?.await;
+    assert!(
+        matches!(result, Err(DeribitError::JsonError(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transport_failure() -> Result<(), Error> {
+    // A mock server dropping the connection without a close frame on the first request
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        ws.next().await;
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, mut subscription) = drb.connect().await?;
+    let result = client.call(GetTimeRequest).await?.await;
+    assert!(
+        matches!(result, Err(DeribitError::CanceledError(_))),
+        "{:?}",
+        result
+    );
+    let reason = timeout(Duration::from_secs(1), subscription.next()).await?;
+    assert!(
+        matches!(reason, Some(Err(DeribitError::WebsocketError(_)))),
+        "{:?}",
+        reason
+    );
+    Ok(())
+}
+
+#[test]
+fn anyhow_compatibility() {
+    let err: DeribitError = anyhow::anyhow!("legacy error").into();
+    assert!(matches!(err, DeribitError::Other(_)));
+    assert_eq!(err.to_string(), "legacy error");
+}
+
+#[test]
+fn api_error_details() {
+    let response = r#"{"jsonrpc":"2.0","id":8,"error":{"message":"Invalid params","data":{"reason":"must be one of the values","param":"kind"},"code":-32602},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}"#;
+    let response: JSONRPCResponse<GetTimeResponse> = from_str(response).unwrap();
+    let err = response.result.right().unwrap();
+    assert_eq!(err.code, -32602);
+    assert_eq!(err.data.unwrap()["param"], "kind");
+
+    let err = DeribitError::ApiError {
+        code: 10028,
+        message: "too_many_requests".into(),
+        data: None,
+    };
+    assert_eq!(
+        err.to_string(),
+        "Deribit API error {code: 10028, message: too_many_requests}"
+    );
+}
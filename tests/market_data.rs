@@ -1,14 +1,24 @@
 use anyhow::Error;
-use chrono::{Duration, Utc};
+use chrono::{Duration, TimeZone, Utc};
 use deribit::{
     models::{
-        market_data::GetHistoricalVolatilityRequest, Currency, GetBookSummaryByCurrencyRequest,
-        GetFundingRateValueRequest, GetIndexPriceRequest, GetInstrumentsRequest,
-        GetOrderBookRequest,
+        accrued_funding, active_instruments, aggregate_book_summary, candles_from_trades,
+        market_data::GetHistoricalVolatilityRequest, next_funding_time, seconds_to_funding,
+        AssetKind, Candle, ComboState, Currency, Direction, FundingRateHistoryEntry,
+        GetBookSummaryByCurrencyRequest, GetBookSummaryByCurrencyResponse,
+        GetBookSummaryByInstrumentRequest, GetComboDetailsRequest, GetComboIdsRequest,
+        GetContractSizeRequest, GetCurrenciesRequest, GetDeliveryPricesRequest,
+        GetFundingChartDataRequest, GetFundingRateHistoryRequest, GetFundingRateValueRequest,
+        GetIndexPriceRequest, GetInstrumentRequest, GetInstrumentsRequest, GetInstrumentsResponse,
+        GetLastSettlementsByInstrumentRequest, GetLastTradesByInstrumentRequest,
+        GetMarkPriceHistoryRequest, GetOrderBookRequest, GetOrderBookResponse,
+        GetTradeVolumesRequest, GetTradingViewChartDataRequest, GetVolatilityIndexDataRequest,
+        GetVolatilityIndexDataResponse, PublicTrade, SettlementType, TickerRequest,
     },
     DeribitBuilder,
 };
 use fehler::{throw, throws};
+use std::time::Duration as StdDuration;
 use tokio::runtime::Runtime;
 
 #[test]
@@ -61,6 +71,103 @@ fn get_instruments() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn instrument_helpers() {
+    let instruments: Vec<GetInstrumentsResponse> = serde_json::from_str(
+        r#"[
+            {"kind":"future","base_currency":"BTC","contract_size":10.0,"creation_timestamp":1534242287000,"expiration_timestamp":32503708800000,"instrument_name":"BTC-PERPETUAL","is_active":true,"min_trade_amount":10.0,"quote_currency":"USD","settlement_currency":"BTC","settlement_period":"perpetual","tick_size":0.5},
+            {"kind":"option","base_currency":"BTC","contract_size":1.0,"creation_timestamp":1554451200000,"expiration_timestamp":1561708800000,"instrument_name":"BTC-28JUN19-7500-P","is_active":false,"min_trade_amount":0.1,"option_type":"put","quote_currency":"BTC","settlement_currency":"BTC","settlement_period":"month","strike":7500.0,"tick_size":0.0005},
+            {"kind":"option","base_currency":"BTC","contract_size":1.0,"creation_timestamp":1554451200000,"expiration_timestamp":1561708800000,"instrument_name":"BTC-28JUN19-9000-C","is_active":true,"min_trade_amount":0.1,"option_type":"call","quote_currency":"BTC","settlement_currency":"BTC","settlement_period":"month","strike":9000.0,"tick_size":0.0005}
+        ]"#,
+    )?;
+
+    assert_eq!(instruments[0].strike(), None);
+    assert_eq!(instruments[1].strike(), Some(7500.));
+    assert_eq!(
+        instruments[1].expiration(),
+        Some(Utc.with_ymd_and_hms(2019, 6, 28, 8, 0, 0).unwrap())
+    );
+    assert!(instruments[0].expiration().unwrap() > Utc::now());
+
+    let active = active_instruments(instruments);
+    let names: Vec<_> = active.iter().map(|i| i.get_instrument_name()).collect();
+    assert_eq!(names, ["BTC-PERPETUAL", "BTC-28JUN19-9000-C"]);
+}
+
+#[test]
+#[throws(Error)]
+fn get_instrument() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetInstrumentRequest::new("BTC-PERPETUAL");
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    match rt.block_on(fut)? {
+        GetInstrumentsResponse::Future {
+            instrument_name, ..
+        } => assert_eq!(instrument_name, "BTC-PERPETUAL"),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn instrument_names() {
+    let _ = env_logger::try_init();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let names = client
+            .instrument_names(Currency::BTC, Some(AssetKind::Future))
+            .await?;
+        assert!(names.iter().any(|name| name == "BTC-PERPETUAL"));
+        let names = client.instrument_names(Currency::BTC, None).await?;
+        assert!(names.iter().any(|name| name == "BTC-PERPETUAL"));
+
+        Ok::<_, Error>(())
+    };
+    let resp = rt.block_on(fut);
+    if let Err(err) = resp {
+        println!("{:?}", err);
+        throw!(err);
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn default_currency_book_summary() {
+    let drb = DeribitBuilder::default()
+        .testnet(true)
+        .default_currency(Currency::ETH)
+        .default_instrument("ETH-PERPETUAL")
+        .build()
+        .unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        assert_eq!(client.default_currency(), Some(Currency::ETH));
+
+        let summaries = client.book_summary().await?;
+        assert!(!summaries.is_empty());
+        for summary in summaries {
+            assert_eq!(summary.base_currency, Currency::ETH);
+        }
+
+        let book = client.order_book().await?;
+        assert_eq!(book.instrument_name, "ETH-PERPETUAL");
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
 #[test]
 #[throws(Error)]
 fn get_book_summary_by_currency() {
@@ -86,6 +193,39 @@ fn get_book_summary_by_currency() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn get_book_summary_by_instrument() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetBookSummaryByInstrumentRequest::new("BTC-PERPETUAL");
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let summary = rt.block_on(fut)?;
+
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].instrument_name, "BTC-PERPETUAL");
+}
+
+#[test]
+#[throws(Error)]
+fn get_contract_size() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetContractSizeRequest::new("BTC-PERPETUAL");
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let resp = rt.block_on(fut)?;
+
+    assert!(resp.contract_size > 0.);
+}
+
 #[test]
 #[throws(Error)]
 fn get_funding_rate_value() {
@@ -135,6 +275,186 @@ fn get_order_book() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn get_funding_rate_history() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let end = Utc::now();
+        let req = GetFundingRateHistoryRequest::new("BTC-PERPETUAL", end - Duration::hours(8), end);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let history = rt.block_on(fut)?;
+    assert!(!history.is_empty());
+    assert!(history.iter().all(|entry| entry.index_price > 0.));
+}
+
+#[test]
+#[throws(Error)]
+fn get_volatility_index_data() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let end = Utc::now();
+        let req =
+            GetVolatilityIndexDataRequest::new(Currency::BTC, end - Duration::days(1), end, "3600");
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let resp = rt.block_on(fut)?;
+
+    assert!(!resp.data.is_empty());
+    for candle in &resp.data {
+        assert!(candle.low <= candle.open && candle.open <= candle.high);
+        assert!(candle.low <= candle.close && candle.close <= candle.high);
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn get_tradingview_chart_data() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let end = Utc::now();
+        let req = GetTradingViewChartDataRequest::new(
+            "BTC-PERPETUAL",
+            end - Duration::minutes(30),
+            end,
+            "1",
+        );
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let resp = rt.block_on(fut)?;
+
+    assert_eq!(resp.status, "ok");
+    let candles = resp.candles();
+    assert_eq!(candles.len(), resp.ticks.len());
+    assert!(candles.len() >= 29);
+    assert!(candles
+        .windows(2)
+        .all(|w| w[1].timestamp - w[0].timestamp == 60_000));
+    for candle in &candles {
+        assert!(candle.low <= candle.open && candle.open <= candle.high);
+        assert!(candle.low <= candle.close && candle.close <= candle.high);
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn volatility_index_candle() {
+    let resp: GetVolatilityIndexDataResponse = serde_json::from_str(
+        r#"{"data":[[1598019300000,0.210084879,0.212860821,0.210084879,0.212860821]],"continuation":null}"#,
+    )?;
+    let candle = &resp.data[0];
+    assert_eq!(candle.timestamp, 1598019300000);
+    assert_eq!(candle.open, 0.210084879);
+    assert_eq!(candle.high, 0.212860821);
+    assert_eq!(candle.close, 0.212860821);
+    assert!(resp.continuation.is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn get_last_settlements_by_instrument() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetLastSettlementsByInstrumentRequest {
+            r#type: Some(SettlementType::Settlement),
+            count: Some(5),
+            ..GetLastSettlementsByInstrumentRequest::new("BTC-PERPETUAL")
+        };
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let resp = rt.block_on(fut)?;
+
+    assert_eq!(resp.settlements.len(), 5);
+    for settlement in &resp.settlements {
+        assert_eq!(settlement.r#type, SettlementType::Settlement);
+        assert_eq!(settlement.instrument_name.as_deref(), Some("BTC-PERPETUAL"));
+        assert!(settlement.mark_price.unwrap() > 0.);
+    }
+    assert!(resp.continuation.is_some());
+}
+
+#[test]
+#[throws(Error)]
+fn get_trade_volumes() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let volumes = client.call(GetTradeVolumesRequest::new()).await?.await?;
+        let extended = client
+            .call(GetTradeVolumesRequest::extended())
+            .await?
+            .await?;
+        Ok::<_, Error>((volumes, extended))
+    };
+    let (volumes, extended) = rt.block_on(fut)?;
+
+    let btc = volumes
+        .iter()
+        .find(|volume| volume.currency == Currency::BTC)
+        .unwrap();
+    assert!(btc.futures_volume > 0.);
+    assert!(btc.futures_volume_7d.is_none());
+
+    let btc = extended
+        .iter()
+        .find(|volume| volume.currency == Currency::BTC)
+        .unwrap();
+    assert!(btc.futures_volume_7d.unwrap() >= btc.futures_volume);
+    assert!(btc.futures_volume_30d.unwrap() >= btc.futures_volume_7d.unwrap());
+}
+
+#[test]
+#[throws(Error)]
+fn get_delivery_prices() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetDeliveryPricesRequest::with_count("btc_usd", 5);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let prices = rt.block_on(fut)?;
+    assert!(!prices.data.is_empty() && prices.data.len() <= 5);
+    assert!(prices.records_total >= prices.data.len() as u64);
+    assert!(prices.data.iter().all(|price| price.delivery_price > 0.));
+}
+
+#[test]
+#[throws(Error)]
+fn get_mark_price_history() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetMarkPriceHistoryRequest::new(
+            "BTC-PERPETUAL",
+            Utc::now() - Duration::hours(1),
+            Utc::now(),
+        );
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let history = rt.block_on(fut)?;
+    assert!(history.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert!(history.iter().all(|entry| entry.1 > 0.));
+}
+
 #[test]
 #[throws(Error)]
 fn get_historical_volatility() {
@@ -161,3 +481,379 @@ fn get_historical_volatility() {
         throw!(err);
     }
 }
+
+fn public_trade(timestamp: u64, trade_seq: u64, price: f64, amount: f64) -> PublicTrade {
+    PublicTrade {
+        amount,
+        direction: Direction::Buy,
+        index_price: price,
+        instrument_name: "BTC-PERPETUAL".into(),
+        iv: None,
+        liquidation: None,
+        price,
+        tick_direction: 0,
+        timestamp,
+        trade_id: trade_seq.to_string(),
+        trade_seq,
+    }
+}
+
+fn book_summary(
+    instrument_name: &str,
+    mark_price: f64,
+    volume: f64,
+    open_interest: &str,
+) -> GetBookSummaryByCurrencyResponse {
+    serde_json::from_str(&format!(
+        r#"{{"volume_usd":{},"volume":{},"quote_currency":"USD","open_interest":{},"mark_price":{},"instrument_name":"{}","creation_timestamp":1625304899374,"base_currency":"BTC"}}"#,
+        volume * mark_price,
+        volume,
+        open_interest,
+        mark_price,
+        instrument_name
+    ))
+    .unwrap()
+}
+
+/// A book on `instrument_name` with the index at 4000, `bids` and `asks` given as JSON arrays.
+fn order_book(instrument_name: &str, bids: &str, asks: &str) -> GetOrderBookResponse {
+    serde_json::from_str(&format!(
+        r#"{{"timestamp":1550757626706,"stats":{{"volume":93.35,"low":3940.75,"high":3976.25}},"state":"open","open_interest":45.27,"min_price":3932.22,"max_price":3971.74,"mark_price":3931.97,"instrument_name":"{}","index_price":4000.0,"bids":{},"best_bid_price":3999.0,"best_bid_amount":0,"best_ask_price":4001.0,"best_ask_amount":0,"asks":{}}}"#,
+        instrument_name, bids, asks
+    ))
+    .unwrap()
+}
+
+#[test]
+fn candles_from_unordered_trades() {
+    let trades = vec![
+        public_trade(61_000, 4, 105., 1.),
+        public_trade(1_000, 1, 100., 10.),
+        public_trade(30_000, 2, 110., 20.),
+        public_trade(59_999, 3, 95., 30.),
+        public_trade(185_000, 5, 120., 5.),
+    ];
+
+    let candles = candles_from_trades(&trades, StdDuration::from_secs(60));
+    let candle = |timestamp, open, high, low, close, volume| Candle {
+        timestamp,
+        open,
+        high,
+        low,
+        close,
+        volume,
+    };
+    assert_eq!(
+        candles,
+        vec![
+            candle(0, 100., 110., 95., 95., 60.),
+            candle(60_000, 105., 105., 105., 105., 1.),
+            candle(120_000, 105., 105., 105., 105., 0.),
+            candle(180_000, 120., 120., 120., 120., 5.),
+        ]
+    );
+}
+
+#[test]
+fn candles_from_no_trades() {
+    assert!(candles_from_trades(&[], StdDuration::from_secs(60)).is_empty());
+}
+
+#[test]
+fn aggregate_book_summaries() {
+    let summaries = vec![
+        book_summary("BTC-PERPETUAL", 30000., 10., "100"),
+        book_summary("BTC-28JUN19", 31000., 30., "null"),
+        book_summary("BTC-27SEP19", 32000., 0., "50"),
+    ];
+
+    let aggregate = aggregate_book_summary(&summaries);
+    assert_eq!(aggregate.volume, 40.);
+    assert_eq!(aggregate.volume_usd, 1_230_000.);
+    assert_eq!(aggregate.open_interest, 150.);
+    assert_eq!(aggregate.mark_price, Some(30750.));
+
+    assert_eq!(aggregate_book_summary(&[]).mark_price, None);
+}
+
+#[test]
+#[throws(Error)]
+fn order_book_ladder() {
+    let book: GetOrderBookResponse = serde_json::from_str(
+        r#"{"timestamp":1550757626706,"stats":{"volume":93.35589552,"low":3940.75,"high":3976.25},"state":"open","settlement_price":3925.85,"open_interest":45.27600333464605,"min_price":3932.22,"max_price":3971.74,"mark_price":3931.97,"last_price":3955.75,"instrument_name":"BTC-PERPETUAL","index_price":3910.46,"funding_8h":0.00455263,"current_funding":0.00500063,"change_id":474988,"bids":[[3954.5,10.0],[3955.75,2.0],[3950.0,5.0]],"best_bid_price":3955.75,"best_bid_amount":2.0,"best_ask_price":3957.0,"best_ask_amount":1.0,"asks":[[3960.0,4.0],[3957.0,1.0]]}"#,
+    )?;
+
+    let ladder = book.ladder(2);
+    let rows: Vec<_> = ladder.lines().collect();
+    assert_eq!(rows.len(), 3);
+
+    let columns = |row: &str| {
+        let (bid, ask) = row.split_once('|').unwrap();
+        (
+            bid.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+            ask.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+        )
+    };
+    assert_eq!(
+        columns(rows[1]),
+        (
+            vec!["2".into(), "2".into(), "3955.75".into()],
+            vec!["3957".into(), "1".into(), "1".into()]
+        )
+    );
+    assert_eq!(
+        columns(rows[2]),
+        (
+            vec!["12".into(), "10".into(), "3954.5".into()],
+            vec!["3960".into(), "4".into(), "5".into()]
+        )
+    );
+    assert_eq!(rows[1].find('|'), rows[0].find('|'));
+}
+
+#[test]
+fn accrued_funding_over_holding_period() {
+    let start = Utc.with_ymd_and_hms(2021, 7, 1, 0, 0, 0).unwrap();
+    let hour = |hours: i64, interest_1h: f64| FundingRateHistoryEntry {
+        timestamp: (start + Duration::hours(hours)).timestamp_millis() as u64,
+        index_price: 35000.,
+        prev_index_price: 35000.,
+        interest_8h: interest_1h * 8.,
+        interest_1h,
+    };
+    let history = vec![
+        hour(1, 0.0001),
+        hour(2, 0.0002),
+        hour(3, -0.0001),
+        hour(4, 0.0004),
+    ];
+
+    // Whole hours: a long of 10000 pays 10000 * (0.0002 - 0.0001)
+    let accrued = accrued_funding(
+        &history,
+        10000.,
+        start + Duration::hours(1),
+        start + Duration::hours(3),
+    );
+    assert!((accrued - -1.).abs() < 1e-9);
+
+    // Half of the first hour and a quarter of the last one
+    let accrued = accrued_funding(
+        &history,
+        10000.,
+        start + Duration::minutes(30),
+        start + Duration::minutes(195),
+    );
+    let expected = -10000. * (0.0001 / 2. + 0.0002 - 0.0001 + 0.0004 / 4.);
+    assert!((accrued - expected).abs() < 1e-9);
+
+    // Shorts receive
+    let accrued = accrued_funding(&history, -10000., start, start + Duration::hours(1));
+    assert!((accrued - 1.).abs() < 1e-9);
+
+    assert_eq!(accrued_funding(&history, 10000., start, start), 0.);
+}
+
+#[test]
+fn next_funding_boundary() {
+    let at = |h, m, s| Utc.with_ymd_and_hms(2021, 7, 1, h, m, s).unwrap();
+    let cases = [
+        (at(0, 0, 1), at(8, 0, 0)),
+        (at(5, 30, 0), at(8, 0, 0)),
+        (at(8, 0, 0), at(16, 0, 0)),
+        (at(12, 0, 0), at(16, 0, 0)),
+        (
+            at(23, 59, 59),
+            Utc.with_ymd_and_hms(2021, 7, 2, 0, 0, 0).unwrap(),
+        ),
+        (at(0, 0, 0), at(8, 0, 0)),
+    ];
+    for (now, next) in cases.iter() {
+        assert_eq!(next_funding_time(*now), *next, "{}", now);
+    }
+
+    assert_eq!(seconds_to_funding(at(7, 59, 0)), 60.);
+    assert_eq!(seconds_to_funding(at(16, 0, 0)), 8. * 3600.);
+    assert_eq!(
+        seconds_to_funding(at(15, 59, 59) + Duration::milliseconds(500)),
+        0.5
+    );
+}
+
+#[test]
+#[throws(Error)]
+fn settlement_currency() {
+    let linear: GetInstrumentsResponse = serde_json::from_str(
+        r#"{"tick_size":0.5,"taker_commission":0.0005,"settlement_period":"perpetual","settlement_currency":"USDC","quote_currency":"USDC","min_trade_amount":0.001,"maker_commission":0.0,"kind":"future","is_active":true,"instrument_name":"BTC_USDC-PERPETUAL","expiration_timestamp":32503708800000,"creation_timestamp":1638316800000,"counter_currency":"USDC","contract_size":0.001,"base_currency":"BTC"}"#,
+    )?;
+    assert_eq!(linear.settlement_currency(), Some(Currency::USDC));
+
+    let inverse: GetInstrumentsResponse = serde_json::from_str(
+        r#"{"tick_size":0.5,"taker_commission":0.0005,"settlement_period":"perpetual","settlement_currency":"BTC","quote_currency":"USD","min_trade_amount":10.0,"maker_commission":0.0,"kind":"future","is_active":true,"instrument_name":"BTC-PERPETUAL","expiration_timestamp":32503708800000,"creation_timestamp":1534242287000,"counter_currency":"USD","contract_size":10.0,"base_currency":"BTC"}"#,
+    )?;
+    assert_eq!(inverse.settlement_currency(), Some(Currency::BTC));
+}
+
+#[test]
+#[throws(Error)]
+fn ticker() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = TickerRequest::new("BTC-PERPETUAL");
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let ticker = rt.block_on(fut)?;
+    assert_eq!(ticker.instrument_name, "BTC-PERPETUAL");
+    assert!(ticker.mark_price > 0.);
+}
+
+#[test]
+#[throws(Error)]
+fn instrument_volumes() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        Ok::<_, Error>(client.instrument_volumes(Currency::BTC).await?)
+    };
+    let volumes = rt.block_on(fut)?;
+    assert!(volumes.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert!(volumes
+        .iter()
+        .take(5)
+        .any(|(name, _)| name == "BTC-PERPETUAL"));
+}
+
+#[test]
+#[throws(Error)]
+fn combo_legs() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let combo = client
+            .instrument_names(Currency::BTC, Some(AssetKind::FutureCombo))
+            .await?
+            .into_iter()
+            .next()
+            .unwrap();
+        Ok::<_, Error>(client.combo_legs(&combo).await?)
+    };
+    let legs = rt.block_on(fut)?;
+    assert_eq!(legs.len(), 2);
+    assert!(legs
+        .iter()
+        .all(|leg| leg.instrument_name.starts_with("BTC-")));
+}
+
+#[test]
+#[throws(Error)]
+fn get_funding_chart_data() {
+    let drb = DeribitBuilder::default().build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetFundingChartDataRequest::new("BTC-PERPETUAL", "8h");
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let chart = rt.block_on(fut)?;
+
+    assert!(!chart.data.is_empty());
+    assert!(chart
+        .data
+        .windows(2)
+        .all(|w| w[0].timestamp < w[1].timestamp));
+    assert!(chart.data.iter().all(|point| point.index_price > 0.));
+    assert!(chart.current_interest.is_finite());
+}
+
+#[test]
+#[throws(Error)]
+fn get_combo_ids() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetComboIdsRequest::with_state(Currency::BTC, ComboState::Active);
+        let ids = client.call(req).await?.await?;
+        let req = GetComboDetailsRequest::new(&ids[0]);
+        let details = client.call(req).await?.await?;
+        Ok::<_, Error>((ids, details))
+    };
+    let (ids, details) = rt.block_on(fut)?;
+
+    assert!(ids.iter().all(|id| id.starts_with("BTC-")));
+    assert_eq!(details.id, ids[0]);
+    assert_eq!(details.state, ComboState::Active);
+    assert!(details.legs.len() >= 2);
+    assert!(details.legs.iter().all(|leg| leg.amount != 0));
+}
+
+#[test]
+#[throws(Error)]
+fn get_last_trades_by_instrument() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = GetLastTradesByInstrumentRequest::with_count("BTC-PERPETUAL", 10);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let resp = rt.block_on(fut)?;
+    assert!(!resp.trades.is_empty());
+    assert!(resp.trades.len() <= 10);
+}
+
+#[test]
+fn normalized_order_books() {
+    // Inverse, sizes in USD
+    let inverse = order_book("BTC-PERPETUAL", "[[3999.0,8000.0]]", "[[4001.0,4001.0]]");
+    // Linear, sizes in BTC
+    let linear = order_book("BTC_USDC-PERPETUAL", "[[3999.0,2.0]]", "[[4001.0,1.0]]");
+
+    let inverse = inverse.normalized(Currency::USD);
+    let linear = linear.normalized(Currency::USDC);
+    assert_eq!(inverse.bids[0].1, 8000.);
+    assert_eq!(linear.bids[0].1, 7998.);
+    assert_eq!(inverse.asks[0].1, linear.asks[0].1);
+
+    // Options, sizes in BTC valued at the index
+    let option = order_book("BTC-29MAR19-4000-C", "[[0.05,1.5]]", "[]");
+    assert_eq!(option.normalized(Currency::BTC).bids[0].1, 6000.);
+}
+
+#[test]
+#[throws(Error)]
+fn get_currencies() {
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        Ok::<_, Error>(client.call(GetCurrenciesRequest).await?.await?)
+    };
+    let currencies = rt.block_on(fut)?;
+    assert!(currencies.iter().any(|c| c.currency == "BTC"));
+    assert!(currencies.iter().any(|c| c.currency == "ETH"));
+}
+
+#[test]
+#[throws(Error)]
+fn spot_requests() {
+    let req = serde_json::to_value(GetInstrumentsRequest::spot(Currency::BTC))?;
+    assert_eq!(req["kind"], "spot");
+    let req = serde_json::to_value(GetBookSummaryByCurrencyRequest::spot(Currency::BTC))?;
+    assert_eq!(req["kind"], "spot");
+}
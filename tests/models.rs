@@ -0,0 +1,308 @@
+use anyhow::Error;
+use chrono::NaiveDate;
+use deribit::models::{
+    market_data::State, AdvanceOption, AssetKind, CancelOnDisconnectScope, CancelOrderType,
+    Channel, ClosePositionType, Currency, Direction, GetOpenOrderType, GrantType, InstrumentKind,
+    InstrumentName, Interval, OptionType, OrderState, OrderType, Priority, PublicSubscribeRequest,
+    TimeInForce, Trigger, WithdrawState,
+};
+use deribit::DeribitError;
+use fehler::throws;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{from_str, to_string};
+use std::fmt::Debug;
+
+#[throws(Error)]
+fn round_trip<T>(cases: &[(T, &str)])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    for (value, wire) in cases {
+        let payload = to_string(value)?;
+        assert_eq!(payload, format!(r#""{}""#, wire));
+        assert_eq!(&from_str::<T>(&payload)?, value);
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn currency() {
+    round_trip(&[
+        (Currency::BTC, "BTC"),
+        (Currency::ETH, "ETH"),
+        (Currency::USDC, "USDC"),
+        (Currency::USDT, "USDT"),
+    ])?;
+    assert_eq!(from_str::<Currency>(r#""btc""#)?, Currency::BTC);
+    assert_eq!(from_str::<Currency>(r#""usdc""#)?, Currency::USDC);
+    assert_eq!(from_str::<Currency>(r#""usdt""#)?, Currency::USDT);
+    assert_eq!("usdc".parse::<Currency>()?, Currency::USDC);
+    assert_eq!(Currency::USDT.to_string(), "USDT");
+}
+
+#[test]
+#[throws(Error)]
+fn asset_kind() {
+    round_trip(&[
+        (AssetKind::Future, "future"),
+        (AssetKind::FutureCombo, "future_combo"),
+        (AssetKind::Option, "option"),
+        (AssetKind::OptionCombo, "option_combo"),
+        (AssetKind::Spot, "spot"),
+    ])?;
+    assert_eq!("future_combo".parse::<AssetKind>()?, AssetKind::FutureCombo);
+    assert_eq!(AssetKind::FutureCombo.to_string(), "future_combo");
+}
+
+#[test]
+#[throws(Error)]
+fn trading_enums() {
+    round_trip(&[
+        (Direction::Buy, "buy"),
+        (Direction::Sell, "sell"),
+        (Direction::Zero, "zero"),
+    ])?;
+    round_trip(&[
+        (OrderType::Limit, "limit"),
+        (OrderType::Market, "market"),
+        (OrderType::StopLimit, "stop_limit"),
+        (OrderType::StopMarket, "stop_market"),
+        (OrderType::TakeLimit, "take_limit"),
+        (OrderType::TakeMarket, "take_market"),
+        (OrderType::MarketLimit, "market_limit"),
+        (OrderType::TrailingStop, "trailing_stop"),
+        (OrderType::Liquidation, "liquidation"),
+    ])?;
+    round_trip(&[
+        (OrderState::Open, "open"),
+        (OrderState::Filled, "filled"),
+        (OrderState::Untriggered, "untriggered"),
+    ])?;
+    round_trip(&[
+        (TimeInForce::GoodTilCancelled, "good_til_cancelled"),
+        (TimeInForce::FillOrKill, "fill_or_kill"),
+        (TimeInForce::ImmediateOrCancel, "immediate_or_cancel"),
+    ])?;
+    round_trip(&[
+        (Trigger::IndexPrice, "index_price"),
+        (Trigger::MarkPrice, "mark_price"),
+        (Trigger::LastPrice, "last_price"),
+    ])?;
+    round_trip(&[(AdvanceOption::USD, "usd"), (AdvanceOption::ImplV, "implv")])?;
+    round_trip(&[
+        (CancelOrderType::All, "all"),
+        (CancelOrderType::Limit, "limit"),
+        (CancelOrderType::Stop, "stop"),
+    ])?;
+    round_trip(&[
+        (GetOpenOrderType::All, "all"),
+        (GetOpenOrderType::Limit, "limit"),
+        (GetOpenOrderType::StopAll, "stop_all"),
+        (GetOpenOrderType::StopLimit, "stop_limit"),
+        (GetOpenOrderType::StopMarket, "stop_market"),
+    ])?;
+    round_trip(&[
+        (ClosePositionType::Limit, "limit"),
+        (ClosePositionType::Market, "market"),
+    ])?;
+    round_trip(&[(OptionType::Call, "call"), (OptionType::Put, "put")])?;
+}
+
+#[test]
+#[throws(Error)]
+fn session_enums() {
+    round_trip(&[
+        (GrantType::Password, "password"),
+        (GrantType::ClientCredentials, "client_credentials"),
+        (GrantType::ClientSignature, "client_signature"),
+        (GrantType::RefreshToken, "refresh_token"),
+    ])?;
+    round_trip(&[
+        (CancelOnDisconnectScope::Connection, "connection"),
+        (CancelOnDisconnectScope::Account, "account"),
+    ])?;
+}
+
+#[test]
+#[throws(Error)]
+fn market_and_wallet_enums() {
+    round_trip(&[(State::Open, "open"), (State::Closed, "closed")])?;
+    round_trip(&[
+        (Priority::Insane, "insane"),
+        (Priority::ExtremeHigh, "extreme_high"),
+        (Priority::VeryLow, "very_low"),
+    ])?;
+    round_trip(&[
+        (WithdrawState::Unconfirmed, "unconfirmed"),
+        (WithdrawState::Confirmed, "confirmed"),
+        (WithdrawState::Completed, "completed"),
+    ])?;
+}
+
+#[test]
+#[throws(Error)]
+fn instrument_name() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+    let perpetual: InstrumentName = "BTC-PERPETUAL".parse()?;
+    assert_eq!(perpetual.currency, Currency::BTC);
+    assert_eq!(perpetual.quote_currency, None);
+    assert_eq!(perpetual.kind, InstrumentKind::Perpetual);
+
+    let future: InstrumentName = "BTC-28JUN19".parse()?;
+    assert_eq!(
+        future.kind,
+        InstrumentKind::Future {
+            expiry: date(2019, 6, 28)
+        }
+    );
+
+    let option: InstrumentName = "BTC-28JUN19-9000-C".parse()?;
+    assert_eq!(
+        option.kind,
+        InstrumentKind::Option {
+            expiry: date(2019, 6, 28),
+            strike: 9000.,
+            option_type: OptionType::Call,
+        }
+    );
+
+    let linear: InstrumentName = "XRP_USDC-5JUL24-0d625-P".parse()?;
+    assert_eq!(linear.currency, Currency::XRP);
+    assert_eq!(linear.quote_currency, Some(Currency::USDC));
+    assert_eq!(
+        linear.kind,
+        InstrumentKind::Option {
+            expiry: date(2024, 7, 5),
+            strike: 0.625,
+            option_type: OptionType::Put,
+        }
+    );
+
+    for name in [
+        "BTC-PERPETUAL",
+        "BTC_USDC-PERPETUAL",
+        "BTC-28JUN19",
+        "ETH-5JUL24",
+        "BTC-28JUN19-9000-C",
+        "XRP_USDC-5JUL24-0d625-P",
+    ]
+    .iter()
+    {
+        assert_eq!(name.parse::<InstrumentName>()?.to_string(), *name);
+    }
+
+    for name in [
+        "",
+        "BTC",
+        "DOGE-PERPETUAL",
+        "BTC-31FEB19",
+        "BTC-28JUN19-9000",
+        "BTC-28JUN19-abc-C",
+        "BTC-28JUN19-9000-X",
+    ]
+    .iter()
+    {
+        match name.parse::<InstrumentName>() {
+            Err(DeribitError::InvalidInstrumentName(invalid)) => assert_eq!(invalid, *name),
+            other => panic!("{}: {:?}", name, other),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn channel_names() {
+    let cases = [
+        (
+            Channel::ticker("BTC-PERPETUAL", Interval::Ms100),
+            "ticker.BTC-PERPETUAL.100ms",
+        ),
+        (
+            Channel::book("BTC-PERPETUAL", "none", 10, Interval::Ms100),
+            "book.BTC-PERPETUAL.none.10.100ms",
+        ),
+        (
+            Channel::book_deltas("ETH-PERPETUAL", Interval::Raw),
+            "book.ETH-PERPETUAL.raw",
+        ),
+        (
+            Channel::trades("BTC-28JUN19-7500-P", Interval::Agg2),
+            "trades.BTC-28JUN19-7500-P.agg2",
+        ),
+        (
+            Channel::TradesByKind {
+                kind: Some(AssetKind::Option),
+                currency: Currency::ETH,
+                interval: Interval::Ms100,
+            },
+            "trades.option.ETH.100ms",
+        ),
+        (
+            Channel::perpetual("BTC-PERPETUAL", Interval::Raw),
+            "perpetual.BTC-PERPETUAL.raw",
+        ),
+        (Channel::quote("BTC-PERPETUAL"), "quote.BTC-PERPETUAL"),
+        (
+            Channel::chart_trades("BTC-PERPETUAL", "60"),
+            "chart.trades.BTC-PERPETUAL.60",
+        ),
+        (
+            Channel::deribit_price_index("btc_usd"),
+            "deribit_price_index.btc_usd",
+        ),
+        (
+            Channel::DeribitPriceRanking("eth_usd".into()),
+            "deribit_price_ranking.eth_usd",
+        ),
+        (
+            Channel::EstimatedExpirationPrice("btc_usd".into()),
+            "estimated_expiration_price.btc_usd",
+        ),
+        (
+            Channel::markprice_options("btc_usd"),
+            "markprice.options.btc_usd",
+        ),
+        (Channel::Announcements, "announcements"),
+        (Channel::PlatformState, "platform_state"),
+        (
+            Channel::UserOrders {
+                kind: None,
+                currency: Currency::BTC,
+                interval: Interval::Raw,
+            },
+            "user.orders.any.BTC.raw",
+        ),
+        (
+            Channel::UserTrades {
+                kind: Some(AssetKind::Future),
+                currency: Currency::BTC,
+                interval: Interval::Ms100,
+            },
+            "user.trades.future.BTC.100ms",
+        ),
+        (
+            Channel::UserChanges {
+                kind: Some(AssetKind::OptionCombo),
+                currency: Currency::ETH,
+                interval: Interval::Raw,
+            },
+            "user.changes.option_combo.ETH.raw",
+        ),
+        (Channel::UserPortfolio(Currency::BTC), "user.portfolio.BTC"),
+    ];
+    for (channel, name) in cases.iter() {
+        assert_eq!(channel.to_string(), *name);
+    }
+    assert!(Channel::UserPortfolio(Currency::BTC).is_private());
+    assert!(!Channel::quote("BTC-PERPETUAL").is_private());
+
+    let req = PublicSubscribeRequest::from_channels(&[
+        Channel::ticker("BTC-PERPETUAL", Interval::Ms100),
+        Channel::PlatformState,
+    ]);
+    assert_eq!(
+        to_string(&req)?,
+        r#"{"channels":["ticker.BTC-PERPETUAL.100ms","platform_state"]}"#
+    );
+}
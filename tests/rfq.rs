@@ -0,0 +1,55 @@
+use anyhow::Error;
+use deribit::{
+    models::{AuthRequest, Currency, Direction, GetRfqsRequest, SendRfqRequest},
+    DeribitBuilder,
+};
+use fehler::throws;
+use std::env::var;
+use tokio::runtime::Runtime;
+
+#[test]
+#[throws(Error)]
+fn get_rfqs() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = GetRfqsRequest::new(Currency::BTC);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let _ = rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn send_rfq() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = SendRfqRequest::with_side("BTC-PERPETUAL", Direction::Buy, 10.);
+        client.call(req).await?.await?;
+
+        let req = GetRfqsRequest::new(Currency::BTC);
+        let rfqs = client.call(req).await?.await?;
+        assert!(rfqs
+            .iter()
+            .any(|rfq| rfq.instrument_name == "BTC-PERPETUAL"));
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
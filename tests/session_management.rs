@@ -0,0 +1,43 @@
+use anyhow::Error;
+use deribit::{
+    models::{
+        AuthRequest, CancelOnDisconnectScope, DisableCancelOnDisconnectRequest,
+        EnableCancelOnDisconnectRequest, GetCancelOnDisconnectRequest,
+    },
+    DeribitBuilder,
+};
+use fehler::throws;
+use std::env::var;
+use tokio::runtime::Runtime;
+
+#[test]
+#[throws(Error)]
+fn cancel_on_disconnect() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = EnableCancelOnDisconnectRequest::with_scope(CancelOnDisconnectScope::Connection);
+        client.call(req).await?.await?;
+
+        let req = GetCancelOnDisconnectRequest::with_scope(CancelOnDisconnectScope::Connection);
+        let status = client.call(req).await?.await?;
+        assert!(status.enabled);
+        assert_eq!(status.scope, CancelOnDisconnectScope::Connection);
+
+        let req = DisableCancelOnDisconnectRequest::with_scope(CancelOnDisconnectScope::Connection);
+        client.call(req).await?.await?;
+
+        let req = GetCancelOnDisconnectRequest::with_scope(CancelOnDisconnectScope::Connection);
+        assert!(!client.call(req).await?.await?.enabled);
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
@@ -1,11 +1,11 @@
 use anyhow::Error;
 use deribit::{
     models::{
-        subscription::{PrivateSubscribeRequest, PublicSubscribeRequest},
-        AuthRequest, BuyRequest, CancelRequest, SellRequest, SubscriptionData, SubscriptionMessage,
-        SubscriptionParams,
+        subscription::{PrivateSubscribeRequest, PublicSubscribeRequest, UserPortfolioChannel},
+        AuthRequest, BuyRequest, CancelRequest, Channel, Currency, GetPositionsRequest, Interval,
+        SellRequest, SubscriptionData, SubscriptionMessage, SubscriptionParams, UserEvent,
     },
-    Deribit, DeribitBuilder, DeribitError,
+    Deribit, DeribitBuilder, DeribitError, PositionTracker, StreamMux,
 };
 use fehler::throws;
 use futures::StreamExt;
@@ -149,6 +149,58 @@ fn deribit_price_index() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn deribit_price_index_data() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await.unwrap();
+
+        let req = PublicSubscribeRequest::new(&[
+            "deribit_price_index.btc_usd".into(),
+            "deribit_price_index.eth_usd".into(),
+        ]);
+        let _ = client.call(req).await.unwrap();
+
+        let v = subscription.take(3).collect::<Vec<_>>().await;
+        Ok::<_, Error>(v)
+    };
+
+    let v = rt.block_on(fut)?;
+    assert_eq!(v.len(), 3);
+    for v in v {
+        match v {
+            Ok(SubscriptionMessage {
+                params: SubscriptionParams::Subscription(SubscriptionData::DeribitPriceIndex(index)),
+                ..
+            }) => {
+                assert_eq!(
+                    index.channel.to_string(),
+                    format!("deribit_price_index.{}", index.data.index_name)
+                );
+                assert!(index.data.price > 0.);
+            }
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn deribit_price_index_frame() {
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"deribit_price_index.btc_usd","data":{"timestamp":1550588002899,"price":3937.89,"index_name":"btc_usd"}}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::DeribitPriceIndex(index)) => {
+            assert_eq!(index.channel.to_string(), "deribit_price_index.btc_usd");
+            assert_eq!(index.data.index_name, "btc_usd");
+            assert_eq!(index.data.price, 3937.89);
+            assert_eq!(index.data.timestamp, 1550588002899);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
 #[test]
 #[throws(Error)]
 fn deribit_price_ranking() {
@@ -242,6 +294,111 @@ fn markprice_options() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn markprice_options_typed() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await.unwrap();
+
+        let req = PublicSubscribeRequest::from_channels(&[Channel::markprice_options("btc_usd")]);
+        let _ = client.call(req).await.unwrap();
+
+        let v = subscription.take(2).collect::<Vec<_>>().await;
+        Ok::<_, Error>(v)
+    };
+
+    let v = rt.block_on(fut)?;
+    assert_eq!(v.len(), 2);
+
+    for v in v {
+        match v {
+            Ok(SubscriptionMessage {
+                params: SubscriptionParams::Subscription(SubscriptionData::MarkPriceOption(marks)),
+                ..
+            }) => {
+                assert_eq!(marks.channel.to_string(), "markprice.options.btc_usd");
+                assert!(!marks.data.is_empty());
+                for mark in marks.data {
+                    assert!(mark.instrument_name.starts_with("BTC-"));
+                    assert!(mark.mark_price >= 0.);
+                }
+            }
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn chart_trades() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await?;
+
+        let req =
+            PublicSubscribeRequest::from_channels(&[Channel::chart_trades("BTC-PERPETUAL", "1")]);
+        let resp = client.call(req).await?.await?;
+        assert_eq!(resp.0, ["chart.trades.BTC-PERPETUAL.1"]);
+
+        let candles = subscription
+            .filter_map(|message| async move {
+                match message.ok()?.params {
+                    SubscriptionParams::Subscription(SubscriptionData::ChartTrades(candle)) => {
+                        Some(candle)
+                    }
+                    _ => None,
+                }
+            })
+            .take(2)
+            .collect::<Vec<_>>()
+            .await;
+        Ok::<_, Error>(candles)
+    };
+
+    let candles = rt.block_on(fut)?;
+    assert_eq!(candles.len(), 2);
+    for candle in &candles {
+        assert_eq!(candle.channel.instrument_name, "BTC-PERPETUAL");
+        assert_eq!(candle.channel.resolution, "1");
+        assert_eq!(candle.data.tick % 60_000, 0);
+        assert!(candle.data.low <= candle.data.close && candle.data.close <= candle.data.high);
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn chart_trades_frame() {
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"chart.trades.BTC-PERPETUAL.1","data":{"volume":0.05219351,"tick":1573645080000,"open":8869.79,"low":8788.25,"high":8870.31,"cost":460,"close":8791.25}}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    assert_eq!(message.channel(), Some("chart.trades.BTC-PERPETUAL.1"));
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::ChartTrades(candle)) => {
+            assert_eq!(candle.channel.to_string(), "chart.trades.BTC-PERPETUAL.1");
+            assert_eq!(candle.data.tick, 1573645080000);
+            assert_eq!(candle.data.close, 8791.25);
+            assert_eq!(candle.data.cost, 460.);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn markprice_options_frame() {
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"markprice.options.btc_usd","data":[{"timestamp":1622470378005,"mark_price":0.0333,"iv":0.9,"instrument_name":"BTC-2JUN21-37000-P"},{"timestamp":1622470378005,"mark_price":0.117,"iv":0.9,"instrument_name":"BTC-4JUN21-40500-P"}]}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::MarkPriceOption(marks)) => {
+            assert_eq!(marks.data.len(), 2);
+            assert_eq!(marks.data[0].instrument_name, "BTC-2JUN21-37000-P");
+            assert_eq!(marks.data[0].mark_price, 0.0333);
+            assert_eq!(marks.data[1].iv, 0.9);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
 #[test]
 #[throws(Error)]
 fn perpetual() {
@@ -273,6 +430,33 @@ fn perpetual() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn platform_state() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, _) = drb.connect().await?;
+        let req = PublicSubscribeRequest::new(&["platform_state".into()]);
+        let resp = client.call(req).await?.await?;
+        Ok::<_, Error>(resp)
+    };
+
+    let resp = rt.block_on(fut)?;
+    assert_eq!(resp.0, ["platform_state"]);
+
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"platform_state","data":{"price_index":"sol_usdc","locked":true}}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::PlatformState(state)) => {
+            assert_eq!(state.channel.to_string(), "platform_state");
+            assert_eq!(state.data.locked, Some(true));
+            assert_eq!(state.data.price_index.as_deref(), Some("sol_usdc"));
+            assert_eq!(state.data.allow_unauthenticated_public_requests, None);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
 #[test]
 #[throws(Error)]
 fn quote() {
@@ -304,6 +488,53 @@ fn quote() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn quote_best_prices() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await.unwrap();
+
+        let req = PublicSubscribeRequest::new(&["quote.BTC-PERPETUAL".into()]);
+        let _ = client.call(req).await.unwrap();
+
+        let v = subscription.take(5).collect::<Vec<_>>().await;
+        Ok::<_, Error>(v)
+    };
+
+    let v = rt.block_on(fut)?;
+    assert_eq!(v.len(), 5);
+
+    for v in v {
+        match v {
+            Ok(SubscriptionMessage {
+                params: SubscriptionParams::Subscription(SubscriptionData::Quote(quote)),
+                ..
+            }) => {
+                assert_eq!(quote.channel.to_string(), "quote.BTC-PERPETUAL");
+                assert_eq!(quote.data.instrument_name, "BTC-PERPETUAL");
+                assert!(quote.data.best_bid_price.is_some() || quote.data.best_ask_price.is_some());
+            }
+            _ => panic!(),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn quote_one_sided() {
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"quote.BTC-27DEC24-150000-C","data":{"timestamp":1550658624149,"instrument_name":"BTC-27DEC24-150000-C","best_bid_price":null,"best_bid_amount":0,"best_ask_price":0.0005,"best_ask_amount":25.0}}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::Quote(quote)) => {
+            assert_eq!(quote.data.best_bid_price, None);
+            assert_eq!(quote.data.best_bid_amount, 0.);
+            assert_eq!(quote.data.best_ask_price, Some(0.0005));
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
 #[test]
 #[throws(Error)]
 fn ticker() {
@@ -344,6 +575,65 @@ fn ticker() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn ticker_channels() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await?;
+
+        let req = PublicSubscribeRequest::from_channels(&[
+            Channel::ticker("BTC-PERPETUAL", Interval::Ms100),
+            Channel::ticker("ETH-PERPETUAL", Interval::Ms100),
+        ]);
+        let _ = client.call(req).await?.await?;
+
+        let v = subscription.take(6).collect::<Vec<_>>().await;
+        Ok::<_, Error>(v)
+    };
+
+    for message in rt.block_on(fut)? {
+        let message = message?;
+        let channel = message.channel().unwrap().to_string();
+        match message.params {
+            SubscriptionParams::Subscription(SubscriptionData::Ticker(ticker)) => {
+                assert_eq!(
+                    channel,
+                    format!("ticker.{}.100ms", ticker.data.instrument_name)
+                );
+                assert_eq!(ticker.channel.to_string(), channel);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn raw_channel() {
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"user.portfolio.btc","data":{"total_pl":0.0,"session_upl":0.0,"session_rpl":0.0,"projected_maintenance_margin":0.0,"projected_initial_margin":0.0,"projected_delta_total":0.0,"portfolio_margining_enabled":false,"options_vega":0.0,"options_value":0.0,"options_theta":0.0,"options_session_upl":0.0,"options_session_rpl":0.0,"options_pl":0.0,"options_gamma":0.0,"options_delta":0.0,"margin_balance":10.0,"maintenance_margin":0.0,"initial_margin":0.0,"futures_session_upl":0.0,"futures_session_rpl":0.0,"futures_pl":0.0,"estimated_liquidation_ratio":0.0,"equity":10.0,"delta_total":0.0,"currency":"BTC","balance":10.0,"available_withdrawal_funds":10.0,"available_funds":10.0}}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    // The parsed channel is normalized, the raw one is kept as sent
+    assert_eq!(message.channel(), Some("user.portfolio.btc"));
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::UserPortfolio(portfolio)) => {
+            assert_eq!(portfolio.channel.to_string(), "user.portfolio.BTC");
+        }
+        other => panic!("{:?}", other),
+    }
+
+    // Deserializers which cannot lend their strings work the same
+    let value: serde_json::Value = serde_json::from_str(frame)?;
+    let message: SubscriptionMessage = serde_json::from_value(value)?;
+    assert_eq!(message.channel(), Some("user.portfolio.btc"));
+    let message: SubscriptionMessage = serde_json::from_reader(frame.as_bytes())?;
+    assert_eq!(message.channel(), Some("user.portfolio.btc"));
+
+    let heartbeat = r#"{"jsonrpc":"2.0","method":"heartbeat","params":{"type":"test_request"}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(heartbeat)?;
+    assert_eq!(message.channel(), None);
+}
+
 #[test]
 #[throws(Error)]
 fn trades() {
@@ -445,6 +735,66 @@ fn user_changes() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn position_tracker() {
+    let SubscriptionTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = SubscriptionTest::default();
+
+    let fut = async move {
+        let (mut client, mut subscription) = drb.connect().await?;
+
+        let _ = client
+            .call(AuthRequest::credential_auth(&key, &secret))
+            .await?
+            .await?;
+
+        let positions = client
+            .call(GetPositionsRequest::futures(Currency::BTC))
+            .await?
+            .await?;
+        let mut tracker = PositionTracker::with_positions(positions);
+        let size = |tracker: &PositionTracker| {
+            tracker
+                .position("BTC-PERPETUAL")
+                .map(|position| position.size())
+                .unwrap_or(0.)
+        };
+        let before = size(&tracker);
+
+        let req = PrivateSubscribeRequest::new(&["user.changes.BTC-PERPETUAL.raw".into()]);
+        let _ = client.call(req).await?.await?;
+
+        sleep(Duration::from_secs(1)).await;
+
+        client
+            .call(BuyRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+
+        while let Some(message) = subscription.next().await {
+            if tracker.apply_message(&message?) && size(&tracker) == before + 10. {
+                break;
+            }
+        }
+        assert_eq!(
+            tracker.snapshot().get("BTC-PERPETUAL").map(|p| p.size()),
+            Some(before + 10.)
+        );
+
+        client
+            .call(SellRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
 #[test]
 #[throws(Error)]
 fn user_orders() {
@@ -536,6 +886,62 @@ fn user_portfolio() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn user_portfolio_after_order() {
+    let SubscriptionTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = SubscriptionTest::default();
+
+    let fut = async move {
+        let (mut client, mut subscription) = drb.connect().await?;
+
+        let _ = client
+            .call(AuthRequest::credential_auth(&key, &secret))
+            .await?
+            .await?;
+
+        let req = PrivateSubscribeRequest::from_channels(&[Channel::UserPortfolio(Currency::BTC)]);
+        let _ = client.call(req).await?.await?;
+
+        let req = BuyRequest::limit("BTC-PERPETUAL", 100., 10.);
+        let id = client.call(req).await?.await?.0.order.order_id;
+
+        let portfolio = loop {
+            match subscription.next().await {
+                Some(Ok(SubscriptionMessage {
+                    params:
+                        SubscriptionParams::Subscription(SubscriptionData::UserPortfolio(portfolio)),
+                    ..
+                })) => break portfolio,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => Err(e)?,
+                None => panic!("Subscription stream closed"),
+            }
+        };
+
+        client.call(CancelRequest::new(&id)).await?.await?;
+        Ok::<_, Error>(portfolio)
+    };
+
+    let portfolio = rt.block_on(fut)?;
+    assert_eq!(portfolio.channel.0, Currency::BTC);
+    assert_eq!(portfolio.data.currency, Currency::BTC);
+    assert!(portfolio.data.equity >= 0.);
+}
+
+#[test]
+#[throws(Error)]
+fn user_portfolio_channel() {
+    let channel: UserPortfolioChannel = serde_json::from_str(r#""user.portfolio.btc""#)?;
+    assert_eq!(channel, UserPortfolioChannel(Currency::BTC));
+    assert_eq!(channel.to_string(), "user.portfolio.BTC");
+    assert!(serde_json::from_str::<UserPortfolioChannel>(r#""user.portfolio.doge""#).is_err());
+}
+
 #[test]
 #[throws(Error)]
 fn user_trades() {
@@ -590,6 +996,47 @@ fn user_trades() {
     }
 }
 
+#[test]
+#[throws(Error)]
+fn subscribe_user() {
+    let SubscriptionTest {
+        rt,
+        drb,
+        key,
+        secret,
+    } = SubscriptionTest::default();
+
+    let fut = async move {
+        let (mut client, subscription) = drb.connect().await?;
+        let _ = client
+            .call(AuthRequest::credential_auth(&key, &secret))
+            .await?
+            .await?;
+
+        let mut events = client.subscribe_user(Currency::BTC, subscription).await?;
+
+        sleep(Duration::from_secs(1)).await;
+
+        let req = BuyRequest::limit("BTC-PERPETUAL", 100f64, 10f64);
+        let id = client.call(req).await?.await?.0.order.order_id;
+
+        let (mut order, mut portfolio) = (false, false);
+        while !(order && portfolio) {
+            match events.next().await {
+                Some(Ok(UserEvent::Order(..))) => order = true,
+                Some(Ok(UserEvent::Portfolio(..))) => portfolio = true,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => Err(e)?,
+                None => panic!("Subscription stream closed"),
+            }
+        }
+
+        client.call(CancelRequest::new(&id)).await?.await?;
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
 #[test]
 #[throws(Error)]
 fn sub_unsub() {
@@ -653,3 +1100,130 @@ fn instrument_state() {
         }
     }
 }
+
+#[test]
+#[throws(Error)]
+fn rfqs() {
+    let frame = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"rfq.btc","data":{"state":true,"side":null,"last_rfq_tstamp":1634816143836,"instrument_name":"BTC-PERPETUAL","amount":null,"traded_volume":0.0}}}"#;
+    let message: SubscriptionMessage = serde_json::from_str(frame)?;
+    match message.params {
+        SubscriptionParams::Subscription(SubscriptionData::Rfqs(rfq)) => {
+            assert_eq!(rfq.channel.to_string(), "rfq.btc");
+            assert_eq!(rfq.data.instrument_name, "BTC-PERPETUAL");
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn ticker_stream() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await?;
+        let tickers = client
+            .ticker_stream("BTC-PERPETUAL", subscription)
+            .await?
+            .take(3)
+            .collect::<Vec<_>>()
+            .await;
+        Ok::<_, Error>(tickers)
+    };
+
+    let tickers = rt.block_on(fut)?;
+    assert_eq!(tickers.len(), 3);
+    for ticker in tickers {
+        let ticker = ticker?;
+        assert_eq!(ticker.instrument_name, "BTC-PERPETUAL");
+        assert!(ticker.mark_price > 0.);
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn trades_stream_live() {
+    let SubscriptionTest { drb, rt, .. } = SubscriptionTest::default();
+
+    let fut = async {
+        let (mut client, subscription) = drb.connect().await?;
+        let trades = client
+            .trades_stream_live("BTC-PERPETUAL", subscription)
+            .await?
+            .take(1)
+            .collect::<Vec<_>>()
+            .await;
+        Ok::<_, Error>(trades)
+    };
+
+    for trade in rt.block_on(fut)? {
+        assert_eq!(trade?.instrument_name, "BTC-PERPETUAL");
+    }
+}
+
+fn trades_frame(trade_seq: u64) -> Result<SubscriptionMessage, DeribitError> {
+    trades_batch(&[trade_seq])
+}
+
+fn trades_batch(trade_seqs: &[u64]) -> Result<SubscriptionMessage, DeribitError> {
+    let trades: Vec<_> = trade_seqs
+        .iter()
+        .map(|trade_seq| {
+            format!(
+                r#"{{"trade_seq":{},"trade_id":"{}","timestamp":1590484512188,"tick_direction":2,"price":8950.0,"instrument_name":"BTC-PERPETUAL","index_price":8955.88,"direction":"sell","amount":10.0}}"#,
+                trade_seq, trade_seq
+            )
+        })
+        .collect();
+    let frame = format!(
+        r#"{{"jsonrpc":"2.0","method":"subscription","params":{{"channel":"trades.BTC-PERPETUAL.100ms","data":[{}]}}}}"#,
+        trades.join(",")
+    );
+    Ok(serde_json::from_str(&frame)?)
+}
+
+#[test]
+#[throws(Error)]
+fn stream_mux_deduplicates() {
+    let rt = Runtime::new()?;
+
+    let first = futures::stream::iter(vec![trades_frame(1), trades_frame(2), trades_frame(4)]);
+    let second = futures::stream::iter(vec![trades_frame(2), trades_frame(3), trades_frame(4)]);
+
+    let merged = rt.block_on(StreamMux::new(vec![first, second]).collect::<Vec<_>>());
+    let mut seqs = vec![];
+    for message in merged {
+        let (source, message) = message?;
+        assert!(source < 2);
+        match message.params {
+            SubscriptionParams::Subscription(SubscriptionData::Trades(trades)) => {
+                seqs.push(trades.data[0].trade_seq)
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+    seqs.sort();
+    assert_eq!(seqs, [1, 2, 3, 4]);
+}
+
+#[test]
+#[throws(Error)]
+fn stream_mux_deduplicates_overlapping_batches() {
+    let rt = Runtime::new()?;
+
+    let first = futures::stream::iter(vec![trades_batch(&[1, 2])]);
+    let second = futures::stream::iter(vec![trades_batch(&[2, 3])]);
+
+    let merged = rt.block_on(StreamMux::new(vec![first, second]).collect::<Vec<_>>());
+    let mut seqs = vec![];
+    for message in merged {
+        match message?.1.params {
+            SubscriptionParams::Subscription(SubscriptionData::Trades(trades)) => {
+                seqs.extend(trades.data.iter().map(|trade| trade.trade_seq))
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+    seqs.sort();
+    assert_eq!(seqs, [1, 2, 3]);
+}
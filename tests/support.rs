@@ -1,10 +1,29 @@
 use anyhow::Error;
 use deribit::{
-    models::{GetTimeRequest, HelloRequest, TestRequest},
-    Deribit,
+    models::{
+        AuthRequest, BuyRequest, GetIndexPriceRequest, GetTimeRequest, HelloRequest,
+        PrivateSubscribeRequest, PublicSubscribeRequest, Retriable, SubscriptionData,
+        SubscriptionParams, TestRequest,
+    },
+    ConnectionState, Deribit, DeribitError,
 };
 use fehler::throws;
-use tokio::runtime::Runtime;
+use futures::{channel::oneshot, future::try_join_all, SinkExt, StreamExt};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{net::TcpListener, runtime::Runtime, time::timeout};
+use tokio_tungstenite::{
+    accept_async,
+    tungstenite::{
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message,
+    },
+};
 
 #[test]
 #[throws(Error)]
@@ -43,6 +62,25 @@ fn get_time() {
     rt.block_on(fut)?;
 }
 
+#[test]
+#[throws(Error)]
+fn call_debug() {
+    let drb = Deribit::new();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async {
+        let (mut client, _) = drb.connect().await?;
+        Ok::<_, Error>(client.call_debug(GetTimeRequest).await?)
+    };
+    let envelope = rt.block_on(fut)?;
+
+    let request: serde_json::Value = serde_json::from_str(&envelope.request)?;
+    assert_eq!(request["method"], "public/get_time");
+    let response: serde_json::Value = serde_json::from_str(&envelope.response)?;
+    assert_eq!(response["id"], request["id"]);
+    assert!(envelope.result.is_ok());
+}
+
 #[test]
 #[throws(Error)]
 fn test() {
@@ -58,3 +96,843 @@ fn test() {
     };
     assert!(rt.block_on(fut).is_err());
 }
+
+#[test]
+#[throws(Error)]
+fn ws_ping_rtt() {
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async {
+        // A mock server, tungstenite answers the pings while the stream is being read
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let drb = Deribit::builder().url(url).build()?;
+        let (mut client, _) = drb.connect().await?;
+
+        let rtt = client.ws_ping_rtt().await?;
+        assert!(rtt > Duration::from_secs(0));
+
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ws_ping_rtt_ignores_unsolicited_pongs() -> Result<(), Error> {
+    // A mock server which sends a pong of its own and never reads, so never answers the ping
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        ws.send(Message::Pong(b"unsolicited".to_vec()))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    });
+
+    let drb = Deribit::builder()
+        .url(url)
+        .timeout(Duration::from_millis(500))
+        .build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let rtt = client.ws_ping_rtt().await;
+    assert!(
+        matches!(rtt, Err(DeribitError::RequestTimeout)),
+        "{:?}",
+        rtt
+    );
+    Ok(())
+}
+
+#[test]
+#[throws(Error)]
+fn interleaved_response_and_notification() {
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async {
+        // A mock server answering the first request, immediately followed by a notification
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws.next().await {
+                if let Message::Text(req) = msg {
+                    let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                    let response = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":1550147385946,"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        req["id"]
+                    );
+                    let notification = r#"{"jsonrpc":"2.0","id":null,"method":"subscription","params":{"channel":"rfq.btc","data":{"state":true,"side":null,"last_rfq_tstamp":1634816143836,"instrument_name":"BTC-PERPETUAL","amount":null,"traded_volume":0.0}}}"#;
+                    ws.send(Message::Text(response)).await.unwrap();
+                    ws.send(Message::Text(notification.into())).await.unwrap();
+                }
+            }
+        });
+
+        let drb = Deribit::builder().url(url).build()?;
+        let (mut client, mut subscription) = drb.connect().await?;
+
+        let time = client.call(GetTimeRequest).await?.await?;
+        assert_eq!(*time, 1550147385946);
+
+        let message = subscription.next().await.unwrap()?;
+        match message.params {
+            SubscriptionParams::Subscription(SubscriptionData::Rfqs(rfq)) => {
+                assert_eq!(rfq.channel.to_string(), "rfq.btc")
+            }
+            other => panic!("{:?}", other),
+        }
+
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reconnect_resubscribes() -> Result<(), Error> {
+    // A mock server notifying once per subscription, which drops the first connection right after
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let (replayed_tx, replayed_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let mut replayed_tx = Some(replayed_tx);
+        for connection in 0.. {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws.next().await {
+                if let Message::Text(req) = msg {
+                    let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                    let response = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":{},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        req["id"], req["params"]["channels"]
+                    );
+                    let notification = r#"{"jsonrpc":"2.0","id":null,"method":"subscription","params":{"channel":"rfq.btc","data":{"state":true,"side":null,"last_rfq_tstamp":1634816143836,"instrument_name":"BTC-PERPETUAL","amount":null,"traded_volume":0.0}}}"#;
+                    ws.send(Message::Text(response)).await.unwrap();
+                    ws.send(Message::Text(notification.into())).await.unwrap();
+                    if connection > 0 {
+                        if let Some(tx) = replayed_tx.take() {
+                            let _ = tx.send(req);
+                        }
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).reconnect(true).build()?;
+    let (mut client, subscription) = drb.connect().await?;
+    let states = client.connection_states();
+
+    let req = PublicSubscribeRequest::new(&["rfq.btc".into()]);
+    let channels = client.call(req).await?.await?;
+    assert_eq!(channels.0, vec!["rfq.btc".to_string()]);
+
+    let messages: Vec<_> = timeout(Duration::from_secs(5), subscription.take(2).collect()).await?;
+    assert_eq!(messages.len(), 2);
+    for message in messages {
+        assert!(matches!(
+            message?.params,
+            SubscriptionParams::Subscription(SubscriptionData::Rfqs(_))
+        ));
+    }
+
+    let replayed = replayed_rx.await?;
+    assert_eq!(replayed["method"], "public/subscribe");
+    assert_eq!(replayed["params"]["channels"][0], "rfq.btc");
+
+    let states: Vec<_> = states.take(2).collect().await;
+    assert_eq!(
+        states,
+        [ConnectionState::Reconnecting, ConnectionState::Connected]
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reconnect_reauthenticates_with_refresh_token() -> Result<(), Error> {
+    // A mock server which drops the first connection right after the authentication
+    // and rejects the authentication on the second one
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let (replayed_tx, replayed_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let mut replayed_tx = Some(replayed_tx);
+        for connection in 0.. {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws.next().await {
+                if let Message::Text(req) = msg {
+                    let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                    if connection == 0 {
+                        let response = format!(
+                            r#"{{"jsonrpc":"2.0","id":{},"result":{{"access_token":"access","expires_in":900,"refresh_token":"refresh","scope":"session:test","token_type":"bearer"}},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                            req["id"]
+                        );
+                        ws.send(Message::Text(response)).await.unwrap();
+                        break;
+                    }
+                    let response = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"error":{{"message":"invalid_token","code":13009}},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        req["id"]
+                    );
+                    ws.send(Message::Text(response)).await.unwrap();
+                    if let Some(tx) = replayed_tx.take() {
+                        let _ = tx.send(req);
+                    }
+                }
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).reconnect(true).build()?;
+    let (mut client, mut subscription) = drb.connect().await?;
+
+    let req = AuthRequest::signature_auth("id", "secret", "nonce", "");
+    let auth = client.call(req).await?.await?;
+    assert_eq!(auth.refresh_token, "refresh");
+
+    let replayed = timeout(Duration::from_secs(5), replayed_rx).await??;
+    assert_eq!(replayed["method"], "public/auth");
+    assert_eq!(replayed["params"]["grant_type"], "refresh_token");
+    assert_eq!(replayed["params"]["refresh_token"], "refresh");
+
+    let failure = timeout(Duration::from_secs(5), subscription.next()).await?;
+    match failure {
+        Some(Err(DeribitError::ApiError { code, .. })) => assert_eq!(code, 13009),
+        other => panic!("{:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn logout_is_final() -> Result<(), Error> {
+    // A mock server closing the connection on logout, as Deribit does
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let connections = Arc::new(AtomicUsize::new(0));
+    let server_connections = connections.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            server_connections.fetch_add(1, Ordering::SeqCst);
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws.next().await {
+                if let Message::Text(req) = msg {
+                    let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                    let result = match req["method"].as_str().unwrap() {
+                        "private/logout" => break,
+                        "public/auth" => r#"{"access_token":"access","expires_in":900,"refresh_token":"refresh","scope":"session:test","token_type":"bearer"}"#.to_string(),
+                        _ => req["params"]["channels"].to_string(),
+                    };
+                    let response = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":{},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        req["id"], result
+                    );
+                    ws.send(Message::Text(response)).await.unwrap();
+                }
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).reconnect(true).build()?;
+    let (mut client, mut subscription) = drb.connect().await?;
+
+    let req = AuthRequest::signature_auth("id", "secret", "nonce", "");
+    client.call(req).await?.await?;
+    let req = PrivateSubscribeRequest::new(&["user.orders.BTC-PERPETUAL.raw".into()]);
+    client.call(req).await?.await?;
+
+    client.logout(true).await?;
+    // The subscription stream ends instead of reporting a failed replay
+    assert!(timeout(Duration::from_secs(1), subscription.next())
+        .await?
+        .is_none());
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+    assert!(matches!(
+        client.call(GetTimeRequest).await,
+        Err(DeribitError::Closed)
+    ));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reconnect_fails_calls_in_flight() -> Result<(), Error> {
+    // A mock server dropping the connection on every request instead of answering it
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(msg)) = ws.next().await {
+                if let Message::Text(_) = msg {
+                    break;
+                }
+            }
+        }
+    });
+
+    let drb = Deribit::builder()
+        .url(url)
+        .reconnect(true)
+        .timeout(Duration::from_secs(2))
+        .build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    // Whether the loss or the waiter of the call reaches the servo first, the call fails
+    // with the connection rather than waiting for its timeout
+    for _ in 0..20 {
+        match client.call(GetTimeRequest).await?.await {
+            Err(DeribitError::CanceledError(_)) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ambient_runtime() -> Result<(), Error> {
+    // A mock server answering every request with the time
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"result":1550147385946,"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"]
+                );
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+    let time = client.call(GetTimeRequest).await?.await?;
+    assert_eq!(*time, 1550147385946);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limit_throttles_bursts() -> Result<(), Error> {
+    // A mock server answering every request with the time
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"result":1550147385946,"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"]
+                );
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).rate_limit(20., 5).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    // The first 5 calls go through at once, the next 10 at 20 per second
+    let start = Instant::now();
+    for _ in 0..15 {
+        let time = client.call(GetTimeRequest).await?.await?;
+        assert_eq!(*time, 1550147385946);
+    }
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(450), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(2), "{:?}", elapsed);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_timeouts() -> Result<(), Error> {
+    // A mock server answering every request with the time, except the first one
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                if req["id"] == 0 {
+                    continue;
+                }
+                let response = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"result":1550147385946,"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"]
+                );
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder()
+        .url(url)
+        .timeout(Duration::from_millis(200))
+        .build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let start = Instant::now();
+    let result = client.call(GetTimeRequest).await?.await;
+    assert!(matches!(result, Err(DeribitError::RequestTimeout)));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+
+    let time = client
+        .call_with_timeout(GetTimeRequest, Duration::from_secs(5))
+        .await?
+        .await?;
+    assert_eq!(*time, 1550147385946);
+    let time = client.call(GetTimeRequest).await?.await?;
+    assert_eq!(*time, 1550147385946);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn timed_out_calls_free_their_slot() -> Result<(), Error> {
+    // A mock server which never answers
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let drb = Deribit::builder()
+        .url(url)
+        .timeout(Duration::from_millis(200))
+        .build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let call = client.call(GetTimeRequest).await?;
+    assert_eq!(client.pending_calls().await?, 1);
+    assert!(matches!(call.await, Err(DeribitError::RequestTimeout)));
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(client.pending_calls().await?, 0);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_code() -> Result<(), Error> {
+    // A mock server closing the connection for a policy violation right away
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let frame = CloseFrame {
+            code: CloseCode::Policy,
+            reason: "too many connections".into(),
+        };
+        ws.close(Some(frame)).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (_client, mut subscription) = drb.connect().await?;
+
+    match timeout(Duration::from_secs(5), subscription.next()).await? {
+        Some(Err(DeribitError::ConnectionClosed { code, reason })) => {
+            assert_eq!(code, 1008);
+            assert_eq!(reason, "too many connections");
+        }
+        other => panic!("{:?}", other),
+    }
+    assert!(subscription.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_many() -> Result<(), Error> {
+    // A mock server answering a batch of requests in reverse order, with their id as price
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut ids = vec![];
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                ids.push(req["id"].as_i64().unwrap());
+                if ids.len() < 5 {
+                    continue;
+                }
+                for id in ids.drain(..).rev() {
+                    let response = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":{{"estimated_delivery_price":{},"index_price":{}}},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        id, id, id
+                    );
+                    ws.send(Message::Text(response)).await.unwrap();
+                }
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let requests = (0..5)
+        .map(|_| GetIndexPriceRequest::new("btc_usd".into()))
+        .collect();
+    let responses = timeout(
+        Duration::from_secs(5),
+        try_join_all(client.call_many(requests).await?),
+    )
+    .await??;
+    let prices: Vec<_> = responses.iter().map(|resp| resp.index_price).collect();
+    assert_eq!(prices, vec![0., 1., 2., 3., 4.]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn graceful_close() -> Result<(), Error> {
+    // A mock server answering after a while and acknowledging the close frame
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let (closed_tx, closed_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(msg)) = ws.next().await {
+            match msg {
+                Message::Text(req) => {
+                    let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    let resp = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":1550147385946,"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        req["id"]
+                    );
+                    ws.send(Message::Text(resp)).await.unwrap();
+                }
+                Message::Close(_) => {
+                    let _ = closed_tx.send(());
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, mut subscription) = drb.connect().await?;
+
+    let in_flight = client.call(GetTimeRequest).await?;
+    client.close(Duration::from_secs(5)).await;
+
+    // Answered before the connection went down
+    assert_eq!(in_flight.await?.0, 1550147385946);
+    timeout(Duration::from_secs(1), closed_rx).await??;
+    assert!(timeout(Duration::from_secs(1), subscription.next())
+        .await?
+        .is_none());
+
+    match client.call(GetTimeRequest).await {
+        Err(DeribitError::Closed) => {}
+        Err(e) => panic!("{}", e),
+        Ok(_) => panic!("a call went through after close"),
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_fails_calls_outliving_the_drain() -> Result<(), Error> {
+    // A mock server which never answers
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    let in_flight = client.call(GetTimeRequest).await?;
+    client.close(Duration::from_millis(100)).await;
+
+    match timeout(Duration::from_secs(1), in_flight).await? {
+        Err(DeribitError::Closed) => {}
+        other => panic!("{:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn auto_heartbeat() -> Result<(), Error> {
+    // A mock server sending test requests and dropping the connection when one is not answered
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut unanswered = 0;
+        let mut ticks = tokio::time::interval(Duration::from_millis(300));
+        loop {
+            tokio::select! {
+                msg = ws.next() => {
+                    let req = match msg {
+                        Some(Ok(Message::Text(req))) => req,
+                        Some(Ok(_)) => continue,
+                        _ => break,
+                    };
+                    let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                    let result = match req["method"].as_str().unwrap() {
+                        "public/set_heartbeat" => r#""ok""#,
+                        "public/test" => {
+                            unanswered -= 1;
+                            r#"{"version":"1.2.26"}"#
+                        }
+                        _ => "1550147385946",
+                    };
+                    let resp = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":{},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                        req["id"], result
+                    );
+                    ws.send(Message::Text(resp)).await.unwrap();
+                }
+                _ = ticks.tick() => {
+                    if unanswered > 0 {
+                        ws.close(None).await.unwrap();
+                        break;
+                    }
+                    let heartbeat = r#"{"jsonrpc":"2.0","method":"heartbeat","params":{"type":"test_request"}}"#;
+                    ws.send(Message::Text(heartbeat.into())).await.unwrap();
+                    unanswered += 1;
+                }
+            }
+        }
+    });
+
+    let drb = Deribit::builder()
+        .url(url)
+        .auto_heartbeat(Duration::from_secs(10))
+        .build()?;
+    let (mut client, mut subscription) = drb.connect().await?;
+
+    // The test requests are answered behind the scenes
+    assert!(timeout(Duration::from_secs(2), subscription.next())
+        .await
+        .is_err());
+    assert_eq!(client.call(GetTimeRequest).await?.await?.0, 1550147385946);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn api_error() -> Result<(), Error> {
+    // A mock server rejecting every request
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let resp = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"error":{{"message":"Invalid params","data":{{"reason":"wrong format","param":"expected_result"}},"code":-32602}},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"]
+                );
+                ws.send(Message::Text(resp)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    match client.call(TestRequest::expect("exception")).await?.await {
+        Err(DeribitError::ApiError {
+            code,
+            message,
+            data: Some(data),
+        }) => {
+            assert_eq!(code, -32602);
+            assert_eq!(message, "Invalid params");
+            assert_eq!(data["param"], "expected_result");
+        }
+        other => panic!("{:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retry_on_overload() -> Result<(), Error> {
+    // A mock server overloaded for the first two time requests and for every buy
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    let buys = Arc::new(AtomicUsize::new(0));
+    let server_buys = buys.clone();
+    let methods = Arc::new(Mutex::new(vec![]));
+    let server_methods = methods.clone();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut times = 0;
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let method = req["method"].as_str().unwrap().to_string();
+                let overloaded = match method.as_str() {
+                    "private/buy" => {
+                        server_buys.fetch_add(1, Ordering::SeqCst);
+                        true
+                    }
+                    "public/get_time" => {
+                        times += 1;
+                        times <= 2
+                    }
+                    _ => false,
+                };
+                let outcome = if overloaded {
+                    r#""error":{"message":"too_many_requests","code":10028}"#
+                } else if method == "public/hello" {
+                    r#""result":{"version":"1.2.26"}"#
+                } else {
+                    r#""result":1550147385946"#
+                };
+                server_methods.lock().unwrap().push(method);
+                let resp = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},{},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"], outcome
+                );
+                ws.send(Message::Text(resp)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder().url(url).retry_on_overload(2).build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    // The retries do not hold the client back
+    let time = client.call(GetTimeRequest).await?;
+    let hello = HelloRequest {
+        client_name: "deribit-rs".into(),
+        client_version: "0.0.1".into(),
+    };
+    assert_eq!(client.call(hello).await?.await?.version, "1.2.26");
+    assert_eq!(time.await?.0, 1550147385946);
+    assert_eq!(
+        *methods.lock().unwrap(),
+        [
+            "public/get_time",
+            "public/hello",
+            "public/get_time",
+            "public/get_time"
+        ]
+    );
+
+    // Order placements are not retried unless asked to
+    let buy = BuyRequest::market("BTC-PERPETUAL", 10.);
+    match client.call(buy.clone()).await?.await {
+        Err(DeribitError::ApiError { code: 10028, .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(buys.load(Ordering::SeqCst), 1);
+
+    match client.call(Retriable(buy)).await?.await {
+        Err(DeribitError::ApiError { code: 10028, .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(buys.load(Ordering::SeqCst), 4);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retries_are_rate_limited() -> Result<(), Error> {
+    // A mock server overloaded for the first request only
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let mut first = true;
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(req) = msg {
+                let req: serde_json::Value = serde_json::from_str(&req).unwrap();
+                let outcome = if first {
+                    r#""error":{"message":"too_many_requests","code":10028}"#
+                } else {
+                    r#""result":1550147385946"#
+                };
+                first = false;
+                let resp = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},{},"usIn":1,"usOut":2,"usDiff":1,"testnet":true}}"#,
+                    req["id"], outcome
+                );
+                ws.send(Message::Text(resp)).await.unwrap();
+            }
+        }
+    });
+
+    let drb = Deribit::builder()
+        .url(url)
+        .rate_limit(2., 1)
+        .retry_on_overload(1)
+        .build()?;
+    let (mut client, _) = drb.connect().await?;
+
+    // The re-send waits for a token, not only for the backoff
+    let start = Instant::now();
+    let time = client.call(GetTimeRequest).await?.await?;
+    assert_eq!(*time, 1550147385946);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(450), "{:?}", elapsed);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connection_states() -> Result<(), Error> {
+    // A mock server acknowledging the close frame
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let drb = Deribit::builder().url(url).build()?;
+    assert_eq!(drb.connection_state(), ConnectionState::Disconnected);
+    let states = drb.connection_states();
+
+    let (mut client, _) = drb.connect().await?;
+    assert_eq!(client.connection_state(), ConnectionState::Connected);
+
+    client.close(Duration::from_secs(1)).await;
+    assert_eq!(client.connection_state(), ConnectionState::Closed);
+
+    let states: Vec<_> = timeout(Duration::from_secs(1), states.collect()).await?;
+    assert_eq!(
+        states,
+        [
+            ConnectionState::Connecting,
+            ConnectionState::Connected,
+            ConnectionState::Closed
+        ]
+    );
+    Ok(())
+}
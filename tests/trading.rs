@@ -1,13 +1,19 @@
 use anyhow::Error;
 use deribit::{
     models::{
-        AuthRequest, BuyRequest, CancelByLabelRequest, CancelRequest, Currency, EditRequest,
-        GetOpenOrdersByCurrencyRequest, GetOpenOrdersByInstrumentRequest, GetOrderStateRequest,
-        SellRequest,
+        maker_ratio, reconcile, split_by_liquidity, AnyCurrency, AssetKind, AuthRequest,
+        BuyRequest, CancelAllByKindOrTypeRequest, CancelByLabelRequest, CancelOrderType,
+        CancelRequest, ClosePositionRequest, ClosePositionType, Currencies, Currency, DesiredQuote,
+        Direction, EditByLabelRequest, EditRequest, Either, GetMarginsRequest, GetMmpConfigRequest,
+        GetOpenOrdersByCurrencyRequest, GetOpenOrdersByInstrumentRequest,
+        GetOpenOrdersByLabelRequest, GetOrderStateRequest, GetTriggerOrderHistoryRequest,
+        GetTriggerOrderHistoryResponse, GetUserTradesByCurrencyRequest, Order, OrderId, OrderState,
+        OrderType, ResetMmpRequest, SellRequest, SetMmpConfigRequest, Trade, Trigger,
     },
-    DeribitBuilder,
+    DeribitBuilder, DeribitError,
 };
 use fehler::throws;
+use serde_json::{from_str, to_string};
 use std::{env::var, time::Duration};
 use tokio::{runtime::Runtime, time::sleep};
 
@@ -31,6 +37,85 @@ fn get_order_state() {
     let _ = rt.block_on(fut)?;
 }
 
+#[test]
+#[throws(Error)]
+fn get_margins() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = GetMarginsRequest::new("BTC-PERPETUAL", 100., 30000.);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let margins = rt.block_on(fut)?;
+
+    assert!(margins.buy > 0.);
+    assert!(margins.sell > 0.);
+    assert!(margins.min_price < margins.max_price);
+}
+
+#[test]
+#[throws(Error)]
+fn mmp_config() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = SetMmpConfigRequest::new("btc_usd", 60, 10).quantity_limit(50.);
+        let set = client.call(req).await?.await?;
+        let req = GetMmpConfigRequest::with_index_name("btc_usd");
+        let configs = client.call(req).await?.await?;
+        client.call(ResetMmpRequest::new("btc_usd")).await?.await?;
+        Ok::<_, Error>((set, configs))
+    };
+    let (set, configs) = rt.block_on(fut)?;
+
+    assert_eq!(set.interval, 60);
+    let config = configs
+        .iter()
+        .find(|config| config.index_name == "btc_usd")
+        .unwrap();
+    assert_eq!(config.interval, 60);
+    assert_eq!(config.frozen_time, 10);
+    assert_eq!(config.quantity_limit, Some(50.));
+}
+
+#[test]
+#[throws(Error)]
+fn cancel_all_by_kind_or_type() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
+        client.call(req).await?.await?;
+        let req = CancelAllByKindOrTypeRequest::new(Currency::BTC)
+            .kind(AssetKind::Future)
+            .order_type(CancelOrderType::Limit);
+        Ok::<_, Error>(client.call(req).await?.await?)
+    };
+    let cancelled = rt.block_on(fut)?;
+    assert!(cancelled.0 >= 1);
+}
+
 #[test]
 #[throws(Error)]
 fn buy_and_sell() {
@@ -113,16 +198,592 @@ fn buy_and_cancel_by_label() {
         let req = AuthRequest::credential_auth(&key, &secret);
         let _ = client.call(req).await?.await?;
 
-        let mut req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
-        req.label = Some("happy".to_string());
+        let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.).label("happy");
+        let order = client.call(req).await?.await?.0.order;
+        assert_eq!(order.label.as_deref(), Some("happy"));
 
-        client.call(req).await?.await?.0.order.order_id;
+        let req = EditByLabelRequest::new("happy", "BTC-PERPETUAL", 1100., 20.);
+        let order = client.call(req).await?.await?.0.order;
+        assert_eq!(order.price, Some(1100.));
 
         client
-            .call(CancelByLabelRequest::new("happy"))
+            .call(CancelByLabelRequest::with_currency("happy", Currency::BTC))
             .await?
             .await?;
         Ok::<_, Error>(())
     };
     let _ = rt.block_on(fut)?;
 }
+
+#[test]
+#[throws(Error)]
+fn get_open_orders_by_label() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.).label("by-label");
+        let order_id = client.call(req).await?.await?.0.order.order_id;
+
+        let req = GetOpenOrdersByLabelRequest::new(Currency::BTC, "by-label");
+        let orders = client.call(req).await?.await?;
+
+        client
+            .call(CancelByLabelRequest::new("by-label"))
+            .await?
+            .await?;
+        Ok::<_, Error>((order_id, orders))
+    };
+    let (order_id, orders) = rt.block_on(fut)?;
+    assert!(orders.iter().any(|order| order.order_id == order_id));
+    assert!(orders
+        .iter()
+        .all(|order| order.label.as_deref() == Some("by-label")));
+}
+
+#[test]
+#[throws(Error)]
+fn close_all() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let future = client
+            .instrument_names(Currency::BTC, Some(AssetKind::Future))
+            .await?
+            .into_iter()
+            .find(|name| name != "BTC-PERPETUAL")
+            .unwrap();
+
+        client
+            .call(BuyRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        client.call(SellRequest::market(future, 10.)).await?.await?;
+        sleep(Duration::from_secs(1)).await;
+
+        let results = client
+            .close_all(Currency::BTC, ClosePositionType::Market)
+            .await?;
+        assert!(results.len() >= 2);
+        for result in results {
+            result?;
+        }
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn estimate_basket_margin() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let basket = [
+            Either::Right(SellRequest::limit("ETH-PERPETUAL", 100000., 1.)),
+            Either::Left(BuyRequest::market("BTC-PERPETUAL", 10.)),
+            Either::Left(BuyRequest::limit("ETH-PERPETUAL", 10., 1.)),
+        ];
+        let margins = client.estimate_basket_margin(&basket).await?;
+        // One total per currency, in a stable order
+        let currencies: Vec<_> = margins.keys().copied().collect();
+        assert_eq!(currencies, [Currency::BTC, Currency::ETH]);
+        assert!(margins[&Currency::BTC] > 0.);
+        assert!(margins[&Currency::ETH] > 0.);
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn order_id_serde_round_trip() {
+    let id = OrderId::from("ETH-1416712080");
+    let payload = to_string(&id)?;
+    assert_eq!(payload, r#""ETH-1416712080""#);
+    assert_eq!(from_str::<OrderId>(&payload)?, id);
+
+    let req = CancelRequest::new(&id);
+    assert_eq!(to_string(&req)?, r#"{"order_id":"ETH-1416712080"}"#);
+}
+
+#[test]
+#[throws(Error)]
+fn reject_post_only_serialization() {
+    let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.).reject_post_only(false);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["post_only"], true);
+    assert_eq!(payload["reject_post_only"], false);
+
+    let req = SellRequest::limit("BTC-PERPETUAL", 1000., 10.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert!(payload.get("post_only").is_none());
+    assert!(payload.get("reject_post_only").is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn order_flags_serialization() {
+    let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    for flag in ["post_only", "reject_post_only", "reduce_only"].iter() {
+        assert!(payload.get(flag).is_none(), "{}", flag);
+    }
+
+    let req = SellRequest::limit("BTC-PERPETUAL", 1000., 10.)
+        .post_only()
+        .reduce_only();
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["post_only"], true);
+    assert_eq!(payload["reduce_only"], true);
+    assert!(payload.get("reject_post_only").is_none());
+
+    let req = BuyRequest::market("BTC-PERPETUAL", 10.).reduce_only();
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["reduce_only"], true);
+    assert!(payload.get("post_only").is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn trigger_orders_serialization() {
+    let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    for field in ["trigger", "trigger_price", "trigger_offset"].iter() {
+        assert!(payload.get(field).is_none(), "{}", field);
+    }
+
+    let req = SellRequest::stop_limit("BTC-PERPETUAL", 10., 990., Trigger::MarkPrice, 1000.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["type"], "stop_limit");
+    assert_eq!(payload["price"], 990.);
+    assert_eq!(payload["trigger"], "mark_price");
+    assert_eq!(payload["trigger_price"], 1000.);
+    assert!(payload.get("trigger_offset").is_none());
+
+    let req = BuyRequest::stop_market("BTC-PERPETUAL", 10., Trigger::IndexPrice, 1000.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["type"], "stop_market");
+    assert_eq!(payload["trigger"], "index_price");
+    assert!(payload.get("price").is_none());
+
+    let req = SellRequest::trailing_stop("BTC-PERPETUAL", 10., Trigger::LastPrice, 50.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["type"], "trailing_stop");
+    assert_eq!(payload["trigger_offset"], 50.);
+    assert!(payload.get("trigger_price").is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn stop_market_untriggered() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        // Far below the market, so it stays untriggered
+        let req = SellRequest::stop_market("BTC-PERPETUAL", 10., Trigger::LastPrice, 100.);
+        let order = client.call(req).await?.await?.0.order;
+        client
+            .call(CancelRequest::new(&order.order_id))
+            .await?
+            .await?;
+        Ok::<_, Error>(order)
+    };
+    let order = rt.block_on(fut)?;
+
+    assert_eq!(order.order_type, OrderType::StopMarket);
+    assert_eq!(order.order_state, OrderState::Untriggered);
+    assert_eq!(order.trigger, Some(Trigger::LastPrice));
+    assert_eq!(order.trigger_price, Some(100.));
+}
+
+#[test]
+#[throws(Error)]
+fn cancel_all_by_kind_or_type_serialization() {
+    let req = CancelAllByKindOrTypeRequest::new(Currency::BTC);
+    assert_eq!(to_string(&req)?, r#"{"currency":"BTC"}"#);
+
+    let req = CancelAllByKindOrTypeRequest::new(vec![Currency::BTC, Currency::ETH])
+        .kind(AssetKind::Option)
+        .order_type(CancelOrderType::Limit);
+    assert_eq!(
+        to_string(&req)?,
+        r#"{"currency":["BTC","ETH"],"kind":"option","type":"limit"}"#
+    );
+
+    let req = CancelAllByKindOrTypeRequest::any();
+    assert_eq!(to_string(&req)?, r#"{"currency":"any"}"#);
+    let req: CancelAllByKindOrTypeRequest = from_str(r#"{"currency":"any"}"#)?;
+    assert_eq!(req.currency, Currencies::Any(AnyCurrency::Any));
+}
+
+#[test]
+#[throws(Error)]
+fn label_requests_serialization() {
+    let req = SellRequest::limit("BTC-PERPETUAL", 1000., 10.).label("quoter");
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["label"], "quoter");
+
+    let req = CancelByLabelRequest::new("quoter");
+    assert_eq!(to_string(&req)?, r#"{"label":"quoter"}"#);
+    let req = CancelByLabelRequest::with_currency("quoter", Currency::ETH);
+    assert_eq!(to_string(&req)?, r#"{"label":"quoter","currency":"ETH"}"#);
+
+    let req = EditByLabelRequest::new("quoter", "BTC-PERPETUAL", 1000., 10.);
+    assert_eq!(
+        to_string(&req)?,
+        r#"{"label":"quoter","instrument_name":"BTC-PERPETUAL","amount":10.0,"price":1000.0}"#
+    );
+}
+
+#[test]
+#[throws(Error)]
+fn market_order_serialization() {
+    let req = BuyRequest::market("BTC-PERPETUAL", 10.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["type"], "market");
+    assert!(payload.get("price").is_none());
+
+    let req = SellRequest::market("BTC-PERPETUAL", 10.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert!(payload.get("price").is_none());
+
+    let req = BuyRequest::limit("BTC-PERPETUAL", 1000., 10.);
+    let payload: serde_json::Value = from_str(&to_string(&req)?)?;
+    assert_eq!(payload["type"], "limit");
+    assert_eq!(payload["price"], 1000.);
+}
+
+#[test]
+#[throws(Error)]
+fn order_type_deserialization() {
+    let cases = [
+        ("limit", OrderType::Limit),
+        ("market", OrderType::Market),
+        ("stop_limit", OrderType::StopLimit),
+        ("stop_market", OrderType::StopMarket),
+        ("take_limit", OrderType::TakeLimit),
+        ("take_market", OrderType::TakeMarket),
+        ("market_limit", OrderType::MarketLimit),
+        ("trailing_stop", OrderType::TrailingStop),
+        ("liquidation", OrderType::Liquidation),
+    ];
+    for (name, order_type) in cases.iter() {
+        assert_eq!(
+            from_str::<OrderType>(&format!(r#""{}""#, name))?,
+            *order_type
+        );
+        assert_eq!(order("1", name, "buy", None, 50.).order_type, *order_type);
+    }
+}
+
+fn fill(trade_id: &str, liquidity: &str, amount: f64) -> Trade {
+    from_str(&format!(
+        r#"{{"trade_seq":30289432,"trade_id":"{}","timestamp":1590484156350,"tick_direction":0,"state":"filled","self_trade":false,"price":8954.0,"order_type":"limit","order_id":"4008965646","matching_id":null,"liquidity":"{}","instrument_name":"BTC-PERPETUAL","index_price":8956.73,"fee_currency":"BTC","fee":0.00000168,"direction":"buy","amount":{}}}"#,
+        trade_id, liquidity, amount
+    ))
+    .unwrap()
+}
+
+/// An open order of `order_type` labelled `quoter`, at the market price when `price` is `None`.
+fn order(
+    order_id: &str,
+    order_type: &str,
+    direction: &str,
+    price: Option<f64>,
+    amount: f64,
+) -> Order {
+    let price = price.map_or("\"market_price\"".to_string(), |price| price.to_string());
+    from_str(&format!(
+        r#"{{"web":false,"time_in_force":"good_til_cancelled","reduce_only":false,"profit_loss":0.0,"price":{},"post_only":true,"order_type":"{}","order_state":"open","order_id":"{}","max_show":{},"last_update_timestamp":1625304899374,"label":"quoter","is_liquidation":false,"instrument_name":"BTC-PERPETUAL","filled_amount":0.0,"direction":"{}","creation_timestamp":1625304899374,"commission":0.0,"average_price":0.0,"api":true,"amount":{}}}"#,
+        price, order_type, order_id, amount, direction, amount
+    ))
+    .unwrap()
+}
+
+fn quote(direction: Direction, price: f64, amount: f64) -> DesiredQuote {
+    DesiredQuote {
+        instrument_name: "BTC-PERPETUAL".into(),
+        direction,
+        price,
+        amount,
+        label: Some("quoter".into()),
+    }
+}
+
+#[test]
+fn reconcile_quotes() {
+    let current = vec![
+        order("1", "limit", "buy", Some(35000.), 100.),
+        order("2", "limit", "sell", Some(35100.), 100.),
+    ];
+
+    // Unchanged quotes
+    let desired = vec![
+        quote(Direction::Buy, 35000., 100.),
+        quote(Direction::Sell, 35100., 100.),
+    ];
+    assert!(reconcile(&current, &desired).is_empty());
+
+    // The ask moves up
+    let desired = vec![
+        quote(Direction::Buy, 35000., 100.),
+        quote(Direction::Sell, 35150., 100.),
+    ];
+    let plan = reconcile(&current, &desired);
+    assert!(plan.cancels.is_empty() && plan.creates.is_empty());
+    assert_eq!(plan.edits.len(), 1);
+    assert_eq!(plan.edits[0].order_id, OrderId::from("2"));
+    assert_eq!(plan.edits[0].price, 35150.);
+
+    // The ask is pulled
+    let desired = vec![quote(Direction::Buy, 35000., 100.)];
+    let plan = reconcile(&current, &desired);
+    assert!(plan.edits.is_empty() && plan.creates.is_empty());
+    assert_eq!(plan.cancels, vec![OrderId::from("2")]);
+
+    // A second bid is added, and the other label is left out
+    let mut other = quote(Direction::Sell, 35100., 100.);
+    other.label = Some("other".into());
+    let desired = vec![
+        quote(Direction::Buy, 35000., 100.),
+        quote(Direction::Buy, 34900., 50.),
+        quote(Direction::Sell, 35100., 100.),
+        other,
+    ];
+    let plan = reconcile(&current, &desired);
+    assert!(plan.cancels.is_empty() && plan.edits.is_empty());
+    assert_eq!(plan.creates.len(), 2);
+    match &plan.creates[0] {
+        Either::Left(buy) => {
+            assert_eq!(buy.price, Some(34900.));
+            assert_eq!(buy.amount, 50.);
+            assert_eq!(buy.label.as_deref(), Some("quoter"));
+        }
+        other => panic!("{:?}", other),
+    }
+    match &plan.creates[1] {
+        Either::Right(sell) => assert_eq!(sell.label.as_deref(), Some("other")),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn reconcile_swapped_quotes() {
+    let current = vec![
+        order("1", "limit", "buy", Some(100.), 10.),
+        order("2", "limit", "buy", Some(99.), 10.),
+    ];
+
+    // The same bids in another order
+    let desired = vec![
+        quote(Direction::Buy, 99., 10.),
+        quote(Direction::Buy, 100., 10.),
+    ];
+    assert!(reconcile(&current, &desired).is_empty());
+
+    // The new bid does not take the order of the one kept
+    let desired = vec![
+        quote(Direction::Buy, 98., 10.),
+        quote(Direction::Buy, 100., 10.),
+    ];
+    let plan = reconcile(&current, &desired);
+    assert!(plan.cancels.is_empty() && plan.creates.is_empty());
+    assert_eq!(plan.edits.len(), 1);
+    assert_eq!(plan.edits[0].order_id, OrderId::from("2"));
+    assert_eq!(plan.edits[0].price, 98.);
+}
+
+#[test]
+fn liquidity_split() {
+    let trades = vec![
+        fill("1", "M", 30.),
+        fill("2", "T", 10.),
+        fill("3", "M", 10.),
+    ];
+
+    let (makers, takers) = split_by_liquidity(&trades);
+    let ids = |trades: Vec<&Trade>| -> Vec<String> {
+        trades.iter().map(|trade| trade.trade_id.clone()).collect()
+    };
+    assert_eq!(ids(makers), ["1", "3"]);
+    assert_eq!(ids(takers), ["2"]);
+    assert_eq!(maker_ratio(&trades), 0.8);
+
+    assert_eq!(split_by_liquidity(&[]).0.len(), 0);
+    assert_eq!(maker_ratio(&[]), 0.);
+}
+
+#[test]
+#[throws(Error)]
+fn get_user_trades_by_currency() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        client
+            .call(BuyRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        client
+            .call(SellRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+
+        let mut req = GetUserTradesByCurrencyRequest::futures(Currency::BTC);
+        req.count = Some(1);
+        let page = client.call(req).await?.await?;
+        assert_eq!(page.trades.len(), 1);
+        assert!(page.has_more);
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn close_position() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        client
+            .call(BuyRequest::market("BTC-PERPETUAL", 10.))
+            .await?
+            .await?;
+        let closed = client
+            .call(ClosePositionRequest::market("BTC-PERPETUAL"))
+            .await?
+            .await?;
+        assert_eq!(closed.order.instrument_name, "BTC-PERPETUAL");
+        assert!(!closed.trades.is_empty());
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn not_enough_funds() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        // Far more than the testnet account can afford
+        let req = BuyRequest::market("BTC-PERPETUAL", 1_000_000_000.);
+        Ok::<_, Error>(client.call(req).await?.await)
+    };
+
+    match rt.block_on(fut)? {
+        Err(DeribitError::ApiError { code, message, .. }) => {
+            assert_eq!(code, 10009);
+            assert_eq!(message, "not_enough_funds");
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn get_trigger_order_history() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        // Far below the market, so it stays untriggered
+        let req = SellRequest::stop_market("BTC-PERPETUAL", 10., Trigger::MarkPrice, 100.);
+        let order = client.call(req).await?.await?.0.order;
+        client
+            .call(CancelRequest::new(&order.order_id))
+            .await?
+            .await?;
+
+        let req = GetTriggerOrderHistoryRequest {
+            count: Some(10),
+            ..GetTriggerOrderHistoryRequest::with_instrument(Currency::BTC, "BTC-PERPETUAL")
+        };
+        let history = client.call(req).await?.await?;
+        Ok::<_, Error>((order, history))
+    };
+    let (order, history) = rt.block_on(fut)?;
+
+    let entries: Vec<_> = history
+        .entries
+        .iter()
+        .filter(|entry| entry.order_id == order.order_id)
+        .collect();
+    assert!(!entries.is_empty());
+    for entry in entries {
+        assert_eq!(entry.instrument_name, "BTC-PERPETUAL");
+        assert_eq!(entry.direction, Direction::Sell);
+        assert_eq!(entry.trigger, Some(Trigger::MarkPrice));
+        assert_eq!(entry.trigger_price, Some(100.));
+    }
+}
+
+#[test]
+#[throws(Error)]
+fn trigger_order_entry() {
+    let history: GetTriggerOrderHistoryResponse = from_str(
+        r#"{"entries":[{"trigger":"index_price","timestamp":1555918941451,"trigger_price":5285.0,"trigger_order_id":"SLIS-103","order_state":"triggered","request":"trigger:order","price":5179.28,"order_id":"671473","offset":277.0,"instrument_name":"BTC-PERPETUAL","amount":10.0,"direction":"buy"}],"continuation":"1555918941451.1"}"#,
+    )?;
+    assert_eq!(history.continuation.as_deref(), Some("1555918941451.1"));
+    let entry = &history.entries[0];
+    assert_eq!(entry.order_state, OrderState::Triggered);
+    assert_eq!(entry.trigger, Some(Trigger::IndexPrice));
+    assert_eq!(entry.trigger_order_id, Some(OrderId("SLIS-103".into())));
+    assert_eq!(entry.trigger_offset, Some(277.));
+    assert_eq!(entry.direction, Direction::Buy);
+}
@@ -0,0 +1,63 @@
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use deribit::util::{expiry, is_stale, strike};
+use std::time::Duration;
+
+#[test]
+fn call_option() {
+    assert_eq!(strike("BTC-28JUN19-7500-C"), Some(7500.));
+    assert_eq!(
+        expiry("BTC-28JUN19-7500-C"),
+        NaiveDate::from_ymd_opt(2019, 6, 28)
+    );
+}
+
+#[test]
+fn put_option() {
+    assert_eq!(strike("ETH-1JUL22-1050-P"), Some(1050.));
+    assert_eq!(
+        expiry("ETH-1JUL22-1050-P"),
+        NaiveDate::from_ymd_opt(2022, 7, 1)
+    );
+    assert_eq!(strike("XRP_USDC-30AUG24-0d625-P"), Some(0.625));
+}
+
+#[test]
+fn future() {
+    assert_eq!(strike("BTC-28JUN19"), None);
+    assert_eq!(expiry("BTC-28JUN19"), None);
+}
+
+#[test]
+fn perpetual() {
+    assert_eq!(strike("BTC-PERPETUAL"), None);
+    assert_eq!(expiry("BTC-PERPETUAL"), None);
+}
+
+#[test]
+fn stale_timestamps() {
+    let now = Utc::now();
+    let max_age = Duration::from_secs(5);
+    let millis_ago = |ago: ChronoDuration| (now - ago).timestamp_millis() as u64;
+
+    assert!(!is_stale(
+        millis_ago(ChronoDuration::milliseconds(100)),
+        now,
+        max_age
+    ));
+    assert!(!is_stale(
+        millis_ago(ChronoDuration::seconds(5)),
+        now,
+        max_age
+    ));
+    assert!(is_stale(
+        millis_ago(ChronoDuration::seconds(6)),
+        now,
+        max_age
+    ));
+    // Clock skew may put the data slightly in the future
+    assert!(!is_stale(
+        millis_ago(ChronoDuration::seconds(-1)),
+        now,
+        max_age
+    ));
+}
@@ -1,9 +1,16 @@
 use anyhow::Error;
 use deribit::{
-    models::{AuthRequest, Currency, GetTransfersRequest},
+    models::{
+        AuthRequest, CancelWithdrawalRequest, Currency, DepositState, GetDepositsRequest,
+        GetDepositsResponse, GetSubaccountsRequest, GetTransfersRequest, GetWithdrawalsRequest,
+        GetWithdrawalsResponse, Priority, SubmitTransferToSubaccountRequest,
+        SubmitTransferToUserRequest, TransferDirection, TransferItem, TransferState, TransferType,
+        WithdrawRequest, WithdrawState,
+    },
     DeribitBuilder,
 };
 use fehler::throws;
+use serde_json::{from_str, json, to_value};
 use std::env::var;
 use tokio::runtime::Runtime;
 
@@ -26,3 +33,139 @@ fn get_transfers() {
     };
     let _ = rt.block_on(fut)?;
 }
+
+#[test]
+#[throws(Error)]
+fn withdraw() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+    // An address of the testnet withdrawal address book
+    let address = var("DERIBIT_WITHDRAW_ADDRESS").unwrap();
+
+    // Never against the mainnet, this moves funds
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = WithdrawRequest::new(Currency::BTC, &address, 0.001).priority(Priority::Low);
+        let withdrawal = client.call(req).await?.await?;
+        assert_eq!(withdrawal.address, address);
+        assert_eq!(withdrawal.state, WithdrawState::Unconfirmed);
+        assert!(withdrawal.transaction_id.is_none());
+
+        let req = CancelWithdrawalRequest::new(Currency::BTC, withdrawal.id);
+        let cancelled = client.call(req).await?.await?;
+        assert_eq!(cancelled.id, withdrawal.id);
+        assert_eq!(cancelled.state, WithdrawState::Cancelled);
+        Ok::<_, Error>(())
+    };
+    rt.block_on(fut)?;
+}
+
+#[test]
+#[throws(Error)]
+fn get_deposits_and_withdrawals() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let req = GetDepositsRequest::with_currency(Currency::ETH).count(10);
+        let deposits = client.call(req).await?.await?;
+        let req = GetWithdrawalsRequest::with_currency(Currency::ETH).count(10);
+        let withdrawals = client.call(req).await?.await?;
+        Ok::<_, Error>((deposits, withdrawals))
+    };
+    let (deposits, withdrawals) = rt.block_on(fut)?;
+
+    assert!(deposits.data.len() as u64 <= deposits.count.min(10));
+    assert!(deposits.data.iter().all(|d| d.currency == Currency::ETH));
+    assert!(withdrawals.data.len() as u64 <= withdrawals.count.min(10));
+    assert!(withdrawals.data.iter().all(|w| w.currency == Currency::ETH));
+}
+
+#[test]
+#[throws(Error)]
+fn deposits_and_withdrawals_pages() {
+    let empty: GetDepositsResponse = from_str(r#"{"count":0,"data":[]}"#)?;
+    assert_eq!(empty.count, 0);
+    assert!(empty.data.is_empty());
+
+    let deposits: GetDepositsResponse = from_str(
+        r#"{"count":1,"data":[{"address":"2N35qDKDY22zmJq9eSyiAerMD4enJ1xx6ax","amount":5,"currency":"BTC","received_timestamp":1549295017670,"state":"completed","transaction_id":"230669110fdaf0a0dbcdc079b6b8b43d5af29cc73683835b9bc6b3406c065fda","updated_timestamp":1549295130159}]}"#,
+    )?;
+    assert_eq!(deposits.data[0].state, DepositState::Completed);
+    assert_eq!(deposits.data[0].received_timestamp, 1549295017670);
+
+    let empty: GetWithdrawalsResponse = from_str(r#"{"count":0,"data":[]}"#)?;
+    assert!(empty.data.is_empty());
+
+    let withdrawals: GetWithdrawalsResponse = from_str(
+        r#"{"count":1,"data":[{"address":"2NBqqD5GRJ8wHy1PYyCXTe9ke5226FhavBz","amount":0.5,"confirmed_timestamp":null,"created_timestamp":1550571443070,"currency":"BTC","fee":0.0001,"id":1,"priority":0.15,"state":"unconfirmed","transaction_id":null,"updated_timestamp":1550571443070}]}"#,
+    )?;
+    assert_eq!(withdrawals.data[0].state, WithdrawState::Unconfirmed);
+    assert!(withdrawals.data[0].transaction_id.is_none());
+}
+
+#[test]
+#[throws(Error)]
+fn submit_transfer_to_subaccount() {
+    let key = var("DERIBIT_KEY").unwrap();
+    let secret = var("DERIBIT_SECRET").unwrap();
+
+    let drb = DeribitBuilder::default().testnet(true).build().unwrap();
+    let rt = Runtime::new().expect("cannot create tokio runtime");
+
+    let fut = async move {
+        let (mut client, _) = drb.connect().await?;
+        let req = AuthRequest::credential_auth(&key, &secret);
+        let _ = client.call(req).await?.await?;
+
+        let subaccounts = client.call(GetSubaccountsRequest::new()).await?.await?;
+        let subaccount = subaccounts
+            .iter()
+            .find(|account| account.r#type == "subaccount")
+            .expect("the testnet account needs a subaccount");
+
+        let req = SubmitTransferToSubaccountRequest::new(Currency::BTC, 0.0001, subaccount.id);
+        let transfer = client.call(req).await?.await?;
+
+        let req = GetTransfersRequest::with_currency(Currency::BTC);
+        let transfers = client.call(req).await?.await?;
+        Ok::<_, Error>((subaccount.username.clone(), transfer, transfers))
+    };
+    let (username, transfer, transfers) = rt.block_on(fut)?;
+
+    assert_eq!(transfer.amount, 0.0001);
+    assert_eq!(transfer.currency, Currency::BTC);
+    assert_eq!(transfer.direction, TransferDirection::Payment);
+    assert_eq!(transfer.r#type, TransferType::Subaccount);
+    assert_eq!(transfer.other_side, username);
+    assert!(transfers.data.iter().any(|t| t.id == transfer.id));
+}
+
+#[test]
+#[throws(Error)]
+fn transfer_requests() {
+    let req = SubmitTransferToUserRequest::new(Currency::ETH, 1.5, "0x3b1f").tfa("123456");
+    assert_eq!(
+        to_value(&req)?,
+        json!({"currency": "ETH", "amount": 1.5, "destination": "0x3b1f", "tfa": "123456"})
+    );
+
+    let transfer: TransferItem = from_str(
+        r#"{"updated_timestamp":1550226218504,"type":"subaccount","state":"confirmed","other_side":"MySubAccount","id":1,"direction":"payment","currency":"ETH","created_timestamp":1550226218504,"amount":12.1234}"#,
+    )?;
+    assert_eq!(transfer.state, TransferState::Confirmed);
+    assert_eq!(transfer.direction, TransferDirection::Payment);
+}